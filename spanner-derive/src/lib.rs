@@ -9,7 +9,7 @@
 //! `#[derive(Table)]` generates the implementation for following traits.
 //! * `TryFromStruct`
 //! * `ToStruct`
-//! * `TryFrom<Row>`
+//! * `TryFrom<Row>` and `TryFrom<&Row>`
 //!
 //! ```
 //! use time::OffsetDateTime;
@@ -104,6 +104,18 @@
 //!    }
 //! }
 //!
+//! impl TryFrom<&Row> for UserCharacter {
+//!    type Error = RowError;
+//!    fn try_from(s: &Row) -> Result<Self, RowError> {
+//!        Ok(UserCharacter {
+//!            user_id: s.column_by_name("UserId")?,
+//!            character_id: s.column_by_name("CharacterId")?,
+//!            level: s.column_by_name("LevelX")?,
+//!            updated_at: s.column_by_name("UpdatedAt")?,
+//!        })
+//!    }
+//! }
+//!
 //! impl TryFrom<Row> for UserCharacter {
 //!    type Error = RowError;
 //!    fn try_from(s: Row) -> Result<Self, RowError> {
@@ -120,7 +132,7 @@
 //! ### Query derive
 //!
 //! `#[derive(Query)]` generates the implementation for following traits.
-//! * `TryFrom<Row>`
+//! * `TryFrom<Row>` and `TryFrom<&Row>`
 //!
 //!```
 //! use google_cloud_spanner::transaction::Transaction;
@@ -27,6 +27,17 @@ pub(crate) fn generate_query_methods(item: ItemStruct) -> impl ToTokens {
             }
         }
 
+        impl TryFrom<&Row> for #struct_name {
+            type Error = RowError;
+            fn try_from(s: &Row) -> Result<Self, RowError> {
+                Ok(#struct_name {
+                    #(
+                        #try_from_struct_fields,
+                    )*
+                })
+            }
+        }
+
         impl TryFrom<Row> for #struct_name {
             type Error = RowError;
             fn try_from(s: Row) -> Result<Self, RowError> {
@@ -132,7 +132,9 @@ async fn test_table_derive() -> Result<(), Error> {
     stmt.add_param("UserID", &user_id);
     let mut reader = tx.query(stmt).await?;
     if let Some(row) = reader.next().await? {
+        let by_ref = User::try_from(&row)?;
         let v: User = row.try_into()?;
+        assert_eq!(by_ref, v);
         assert_eq!(v.user_id, user_id);
         assert_eq!(&v.not_null_numeric.to_string(), "-99999999999999999999999999999.999999999");
         assert!(v.updated_at.unix_timestamp() >= now);
@@ -1,3 +1,12 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures_util::stream::{self, Stream};
+use parking_lot::RwLock;
+use prost::Message;
+use prost_types::Timestamp;
+
 use google_cloud_gax::conn::Channel;
 use google_cloud_gax::create_request;
 use google_cloud_gax::grpc::{Response, Status};
@@ -8,20 +17,97 @@ use google_cloud_googleapis::iam::v1::{
 use google_cloud_googleapis::longrunning::Operation as InternalOperation;
 use google_cloud_googleapis::spanner::admin::database::v1::database_admin_client::DatabaseAdminClient as InternalDatabaseAdminClient;
 use google_cloud_googleapis::spanner::admin::database::v1::{
-    Backup, CreateBackupRequest, CreateDatabaseRequest, Database, DeleteBackupRequest, DropDatabaseRequest,
-    GetBackupRequest, GetDatabaseDdlRequest, GetDatabaseDdlResponse, GetDatabaseRequest, ListBackupOperationsRequest,
-    ListBackupsRequest, ListDatabaseOperationsRequest, ListDatabasesRequest, RestoreDatabaseRequest,
-    UpdateBackupRequest, UpdateDatabaseDdlRequest,
+    Backup, CopyBackupMetadata, CreateBackupMetadata, CreateBackupRequest, CreateDatabaseRequest, Database,
+    DeleteBackupRequest, DropDatabaseRequest, GetBackupRequest, GetDatabaseDdlRequest, GetDatabaseDdlResponse,
+    GetDatabaseRequest, ListBackupOperationsRequest, ListBackupsRequest, ListDatabaseOperationsRequest,
+    ListDatabasesRequest, RestoreDatabaseRequest, UpdateBackupRequest, UpdateDatabaseDdlRequest,
 };
 use google_cloud_longrunning::autogen::operations_client::OperationsClient;
 use google_cloud_longrunning::longrunning::Operation;
 
 use crate::admin::default_retry_setting;
 
+/// BackupOperation is the progress of a CreateBackup or CopyBackup long-running operation.
+#[derive(Clone, Debug)]
+pub struct BackupOperation {
+    /// The name of the long-running operation.
+    pub name: String,
+    /// The name of the backup being created or copied.
+    pub backup_name: String,
+    pub done: bool,
+    pub progress_percent: Option<i32>,
+    pub start_time: Option<Timestamp>,
+    /// The time at which the operation failed or completed successfully.
+    pub end_time: Option<Timestamp>,
+}
+
+impl BackupOperation {
+    /// from_operation decodes the metadata of a backup operation.
+    /// It returns None for operations that are neither CreateBackup nor CopyBackup.
+    pub fn from_operation(operation: &InternalOperation) -> Option<Self> {
+        let metadata = operation.metadata.as_ref()?;
+        let (backup_name, progress) = if metadata.type_url.ends_with("CreateBackupMetadata") {
+            let m = CreateBackupMetadata::decode(metadata.value.as_slice()).ok()?;
+            (m.name, m.progress)
+        } else if metadata.type_url.ends_with("CopyBackupMetadata") {
+            let m = CopyBackupMetadata::decode(metadata.value.as_slice()).ok()?;
+            (m.name, m.progress)
+        } else {
+            return None;
+        };
+        Some(BackupOperation {
+            name: operation.name.to_string(),
+            backup_name,
+            done: operation.done,
+            progress_percent: progress.as_ref().map(|p| p.progress_percent),
+            start_time: progress.as_ref().and_then(|p| p.start_time),
+            end_time: progress.and_then(|p| p.end_time),
+        })
+    }
+}
+
+/// DdlCache is the cache of get_database_ddl_cached.
+#[derive(Default)]
+pub(crate) struct DdlCache {
+    /// database name -> (fetched at, DDL statements)
+    entries: HashMap<String, (Instant, Arc<Vec<String>>)>,
+    /// database name -> number of invalidations
+    generations: HashMap<String, u64>,
+}
+
+impl DdlCache {
+    pub(crate) fn get(&self, database: &str, ttl: Duration) -> Option<Arc<Vec<String>>> {
+        self.entries
+            .get(database)
+            .filter(|(fetched_at, _)| fetched_at.elapsed() < ttl)
+            .map(|(_, statements)| Arc::clone(statements))
+    }
+
+    /// generation is taken before fetching the statements, and passed to insert after.
+    pub(crate) fn generation(&self, database: &str) -> u64 {
+        self.generations.get(database).copied().unwrap_or_default()
+    }
+
+    /// insert caches the statements unless the database has been invalidated since generation was taken,
+    /// in which case they may predate the invalidating change.
+    pub(crate) fn insert(&mut self, database: &str, generation: u64, statements: Arc<Vec<String>>) {
+        if self.generation(database) == generation {
+            self.entries.insert(database.to_string(), (Instant::now(), statements));
+        }
+    }
+
+    pub(crate) fn invalidate(&mut self, database: &str) {
+        self.entries.remove(database);
+        *self.generations.entry(database.to_string()).or_default() += 1;
+    }
+}
+
 #[derive(Clone)]
 pub struct DatabaseAdminClient {
     inner: InternalDatabaseAdminClient<Channel>,
     lro_client: OperationsClient,
+    // A blocking lock is fine here because its guards are never held across an `.await`.
+    ddl_cache: Arc<RwLock<DdlCache>>,
 }
 
 impl DatabaseAdminClient {
@@ -29,6 +115,7 @@ impl DatabaseAdminClient {
         Self {
             inner: InternalDatabaseAdminClient::new(channel).max_decoding_message_size(i32::MAX as usize),
             lro_client,
+            ddl_cache: Arc::new(RwLock::new(DdlCache::default())),
         }
     }
 
@@ -115,9 +202,11 @@ impl DatabaseAdminClient {
             let request = create_request(format!("database={database}"), req.clone());
             self.inner.clone().update_database_ddl(request).await
         };
-        invoke(retry, action)
-            .await
-            .map(|d| Operation::new(self.lro_client.clone(), d.into_inner()))
+        let result = invoke(retry, action).await;
+        if result.is_ok() {
+            self.ddl_cache.write().invalidate(database);
+        }
+        result.map(|d| Operation::new(self.lro_client.clone(), d.into_inner()))
     }
 
     /// drop_database drops (aka deletes) a Cloud Spanner database.
@@ -135,7 +224,11 @@ impl DatabaseAdminClient {
             let request = create_request(format!("database={database}"), req.clone());
             self.inner.clone().drop_database(request).await
         };
-        invoke(retry, action).await
+        let result = invoke(retry, action).await;
+        if result.is_ok() {
+            self.ddl_cache.write().invalidate(database);
+        }
+        result
     }
 
     /// get_database_ddl returns the schema of a Cloud Spanner database as a list of formatted
@@ -156,6 +249,35 @@ impl DatabaseAdminClient {
         invoke(retry, action).await
     }
 
+    /// get_database_ddl_cached returns the DDL statements of the database like get_database_ddl,
+    /// but reuses the previously fetched statements until `ttl` has elapsed.
+    /// The cached statements of a database are discarded when update_database_ddl or drop_database
+    /// for the database succeeds, and a fetch that overlaps such a call is not cached.
+    /// Note that the schema change requested by update_database_ddl may still be running,
+    /// and get_database_ddl does not show pending schema updates.
+    pub async fn get_database_ddl_cached(
+        &self,
+        database: &str,
+        ttl: Duration,
+        retry: Option<RetrySetting>,
+    ) -> Result<Arc<Vec<String>>, Status> {
+        let generation = {
+            let cache = self.ddl_cache.read();
+            if let Some(statements) = cache.get(database, ttl) {
+                return Ok(statements);
+            }
+            cache.generation(database)
+        };
+        let req = GetDatabaseDdlRequest {
+            database: database.to_string(),
+        };
+        let statements = Arc::new(self.get_database_ddl(req, retry).await?.into_inner().statements);
+        self.ddl_cache
+            .write()
+            .insert(database, generation, Arc::clone(&statements));
+        Ok(statements)
+    }
+
     /// set_iam_policy sets the access control policy on a database or backup resource.
     /// Replaces any existing policy.
     ///
@@ -402,6 +524,49 @@ impl DatabaseAdminClient {
         }
     }
 
+    /// list_backup_progress streams the backup operations in the given instance page by page
+    /// like list_backup_operations, with their metadata decoded into BackupOperation.
+    /// Operations whose metadata is not a CreateBackupMetadata are skipped.
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    pub fn list_backup_progress(
+        &self,
+        req: ListBackupOperationsRequest,
+        retry: Option<RetrySetting>,
+    ) -> impl Stream<Item = Result<BackupOperation, Status>> + '_ {
+        let retry = retry.unwrap_or_else(default_retry_setting);
+        let pages = (VecDeque::new(), Some(req));
+        stream::try_unfold(pages, move |(mut operations, mut next)| {
+            let retry = retry.clone();
+            async move {
+                while operations.is_empty() {
+                    let mut req = match next.take() {
+                        Some(req) => req,
+                        None => break,
+                    };
+                    let action = || async {
+                        let request = create_request(format!("parent={}", req.parent), req.clone());
+                        self.inner
+                            .clone()
+                            .list_backup_operations(request)
+                            .await
+                            .map(|d| d.into_inner())
+                    };
+                    let response = invoke(Some(retry.clone()), action).await?;
+                    operations = response
+                        .operations
+                        .iter()
+                        .filter_map(BackupOperation::from_operation)
+                        .collect();
+                    if !response.next_page_token.is_empty() {
+                        req.page_token = response.next_page_token;
+                        next = Some(req);
+                    }
+                }
+                Ok(operations.pop_front().map(|op| (op, (operations, next))))
+            }
+        })
+    }
+
     /// list_database_operations lists database [longrunning-operations][google.longrunning.Operation].
     /// A database operation has a name of the form
     /// projects/<project>/instances/<instance>/databases/<database>/operations/<operation>.
@@ -2,19 +2,24 @@ pub mod database_admin_client;
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use prost::Message;
+    use prost_types::{Any, Timestamp};
     use serial_test::serial;
     use time::OffsetDateTime;
 
     use google_cloud_gax::conn::{ConnectionManager, ConnectionOptions, Environment};
     use google_cloud_googleapis::spanner::admin::database::v1::database::State;
 
+    use google_cloud_googleapis::longrunning::Operation;
     use google_cloud_googleapis::spanner::admin::database::v1::{
-        CreateDatabaseRequest, Database, DatabaseDialect, DropDatabaseRequest, GetDatabaseDdlRequest,
-        GetDatabaseRequest, ListDatabasesRequest, UpdateDatabaseDdlRequest,
+        CreateBackupMetadata, CreateDatabaseRequest, Database, DatabaseDialect, DropDatabaseRequest,
+        GetDatabaseDdlRequest, GetDatabaseRequest, ListDatabasesRequest, OperationProgress, UpdateDatabaseDdlRequest,
     };
     use google_cloud_longrunning::autogen::operations_client::OperationsClient;
 
-    use crate::admin::database::database_admin_client::DatabaseAdminClient;
+    use crate::admin::database::database_admin_client::{BackupOperation, DatabaseAdminClient, DdlCache};
     use crate::apiv1::conn_pool::{AUDIENCE, SPANNER};
 
     async fn new_client() -> DatabaseAdminClient {
@@ -139,4 +144,81 @@ mod tests {
         };
         let _ = update_result.unwrap();
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_get_database_ddl_cached() {
+        let database = create_database().await;
+        let client = new_client().await;
+        let ttl = Duration::from_secs(60);
+
+        let first = client.get_database_ddl_cached(&database.name, ttl, None).await.unwrap();
+        assert_eq!(first.len(), 1);
+        let second = client.get_database_ddl_cached(&database.name, ttl, None).await.unwrap();
+        assert!(std::sync::Arc::ptr_eq(&first, &second));
+
+        let request = UpdateDatabaseDdlRequest {
+            database: database.name.to_string(),
+            statements: vec!["CREATE TABLE Tbl1 (ID INT64) PRIMARY KEY(ID)".to_string()],
+            operation_id: "".to_string(),
+        };
+        let mut operation = client.update_database_ddl(request, None).await.unwrap();
+        operation.wait(None).await.unwrap();
+
+        let updated = client.get_database_ddl_cached(&database.name, ttl, None).await.unwrap();
+        assert_eq!(updated.len(), 2);
+    }
+
+    #[test]
+    fn test_ddl_cache_invalidated_during_fetch() {
+        let ttl = Duration::from_secs(60);
+        let mut cache = DdlCache::default();
+
+        // update_database_ddl succeeds while the old statements are being fetched
+        let generation = cache.generation("db");
+        cache.invalidate("db");
+        cache.insert("db", generation, std::sync::Arc::new(vec!["old".to_string()]));
+        assert!(cache.get("db", ttl).is_none());
+
+        let generation = cache.generation("db");
+        cache.insert("db", generation, std::sync::Arc::new(vec!["new".to_string()]));
+        assert_eq!(*cache.get("db", ttl).unwrap(), vec!["new".to_string()]);
+        assert!(cache.get("db", Duration::ZERO).is_none());
+        assert!(cache.get("other", ttl).is_none());
+    }
+
+    #[test]
+    fn test_backup_operation_from_operation() {
+        let metadata = CreateBackupMetadata {
+            name: "projects/p/instances/i/backups/b".to_string(),
+            database: "projects/p/instances/i/databases/d".to_string(),
+            progress: Some(OperationProgress {
+                progress_percent: 42,
+                start_time: Some(Timestamp { seconds: 1, nanos: 0 }),
+                end_time: None,
+            }),
+            cancel_time: None,
+        };
+        let mut operation = Operation {
+            name: "projects/p/instances/i/backups/b/operations/o".to_string(),
+            metadata: Some(Any {
+                type_url: "type.googleapis.com/google.spanner.admin.database.v1.CreateBackupMetadata".to_string(),
+                value: metadata.encode_to_vec(),
+            }),
+            done: false,
+            result: None,
+        };
+
+        let progress = BackupOperation::from_operation(&operation).unwrap();
+        assert_eq!(progress.name, operation.name);
+        assert_eq!(progress.backup_name, "projects/p/instances/i/backups/b");
+        assert!(!progress.done);
+        assert_eq!(progress.progress_percent, Some(42));
+        assert_eq!(progress.start_time.unwrap().seconds, 1);
+        assert!(progress.end_time.is_none());
+
+        operation.metadata.as_mut().unwrap().type_url =
+            "type.googleapis.com/google.spanner.admin.database.v1.RestoreDatabaseMetadata".to_string();
+        assert!(BackupOperation::from_operation(&operation).is_none());
+    }
 }
@@ -0,0 +1,619 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures_util::future::BoxFuture;
+use parking_lot::Mutex;
+use time::OffsetDateTime;
+use tokio::task::JoinSet;
+
+use google_cloud_gax::grpc::Status;
+
+use crate::client::{Client, Error};
+use crate::key::{all_keys, Key};
+use crate::mutation::{delete, insert_or_update};
+use crate::row::{Error as RowError, Struct, TryFromStruct};
+use crate::statement::Statement;
+use crate::transaction::QueryOptions;
+
+#[derive(Clone, Debug)]
+pub struct ChangeRecord {
+    pub data_change_record: Vec<DataChangeRecord>,
+    pub heartbeat_record: Vec<HeartbeatRecord>,
+    pub child_partitions_record: Vec<ChildPartitionsRecord>,
+}
+
+impl ChangeRecord {
+    /// timestamp is the latest commit or heartbeat timestamp of the record, up to which the partition is read.
+    fn timestamp(&self) -> Option<OffsetDateTime> {
+        let commits = self.data_change_record.iter().map(|r| r.commit_timestamp);
+        let heartbeats = self.heartbeat_record.iter().map(|r| r.timestamp);
+        commits.chain(heartbeats).max()
+    }
+}
+
+impl TryFromStruct for ChangeRecord {
+    fn try_from_struct(s: Struct<'_>) -> Result<Self, RowError> {
+        Ok(Self {
+            data_change_record: s.column_by_name("data_change_record")?,
+            heartbeat_record: s.column_by_name("heartbeat_record")?,
+            child_partitions_record: s.column_by_name("child_partitions_record")?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DataChangeRecord {
+    pub commit_timestamp: OffsetDateTime,
+    pub record_sequence: String,
+    pub server_transaction_id: String,
+    pub is_last_record_in_transaction_in_partition: bool,
+    pub table_name: String,
+    pub column_types: Vec<ColumnType>,
+    pub mods: Vec<Mod>,
+    pub mod_type: String,
+    pub value_capture_type: String,
+    pub number_of_records_in_transaction: i64,
+    pub number_of_partitions_in_transaction: i64,
+    pub transaction_tag: String,
+    pub is_system_transaction: bool,
+}
+
+impl TryFromStruct for DataChangeRecord {
+    fn try_from_struct(s: Struct<'_>) -> Result<Self, RowError> {
+        Ok(Self {
+            commit_timestamp: s.column_by_name("commit_timestamp")?,
+            record_sequence: s.column_by_name("record_sequence")?,
+            server_transaction_id: s.column_by_name("server_transaction_id")?,
+            is_last_record_in_transaction_in_partition: s
+                .column_by_name("is_last_record_in_transaction_in_partition")?,
+            table_name: s.column_by_name("table_name")?,
+            column_types: s.column_by_name("column_types")?,
+            mods: s.column_by_name("mods")?,
+            mod_type: s.column_by_name("mod_type")?,
+            value_capture_type: s.column_by_name("value_capture_type")?,
+            number_of_records_in_transaction: s.column_by_name("number_of_records_in_transaction")?,
+            number_of_partitions_in_transaction: s.column_by_name("number_of_partitions_in_transaction")?,
+            transaction_tag: s.column_by_name("transaction_tag")?,
+            is_system_transaction: s.column_by_name("is_system_transaction")?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ColumnType {
+    pub name: String,
+    /// column_type is the JSON representation of the type, e.g. `{"code":"STRING"}`.
+    pub column_type: String,
+    pub is_primary_key: bool,
+    pub ordinal_position: i64,
+}
+
+impl TryFromStruct for ColumnType {
+    fn try_from_struct(s: Struct<'_>) -> Result<Self, RowError> {
+        Ok(Self {
+            name: s.column_by_name("name")?,
+            column_type: s.column_by_name("type")?,
+            is_primary_key: s.column_by_name("is_primary_key")?,
+            ordinal_position: s.column_by_name("ordinal_position")?,
+        })
+    }
+}
+
+/// Mod is a changed row, the keys and values are JSON objects keyed by column name.
+#[derive(Clone, Debug)]
+pub struct Mod {
+    pub keys: String,
+    pub new_values: String,
+    pub old_values: String,
+}
+
+impl TryFromStruct for Mod {
+    fn try_from_struct(s: Struct<'_>) -> Result<Self, RowError> {
+        Ok(Self {
+            keys: s.column_by_name("keys")?,
+            new_values: s.column_by_name("new_values")?,
+            old_values: s.column_by_name("old_values")?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HeartbeatRecord {
+    pub timestamp: OffsetDateTime,
+}
+
+impl TryFromStruct for HeartbeatRecord {
+    fn try_from_struct(s: Struct<'_>) -> Result<Self, RowError> {
+        Ok(Self {
+            timestamp: s.column_by_name("timestamp")?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChildPartitionsRecord {
+    pub start_timestamp: OffsetDateTime,
+    pub record_sequence: String,
+    pub child_partitions: Vec<ChildPartition>,
+}
+
+impl TryFromStruct for ChildPartitionsRecord {
+    fn try_from_struct(s: Struct<'_>) -> Result<Self, RowError> {
+        Ok(Self {
+            start_timestamp: s.column_by_name("start_timestamp")?,
+            record_sequence: s.column_by_name("record_sequence")?,
+            child_partitions: s.column_by_name("child_partitions")?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChildPartition {
+    pub token: String,
+    pub parent_partition_tokens: Vec<String>,
+}
+
+impl TryFromStruct for ChildPartition {
+    fn try_from_struct(s: Struct<'_>) -> Result<Self, RowError> {
+        Ok(Self {
+            token: s.column_by_name("token")?,
+            parent_partition_tokens: s.column_by_name("parent_partition_tokens")?,
+        })
+    }
+}
+
+/// PartitionCheckpoint is the position up to which a partition has been handled.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct PartitionCheckpoint {
+    pub token: String,
+    pub timestamp: OffsetDateTime,
+    /// parent_partition_tokens are the partitions that must be finished before this one is read.
+    pub parent_partition_tokens: Vec<String>,
+}
+
+/// ChangeStreamCheckpointStore persists the progress of a ChangeStreamConsumer so that it can be resumed.
+pub trait ChangeStreamCheckpointStore: Send + Sync {
+    /// load returns the partitions that were not finished by the previous run.
+    /// If it returns none, the consumer starts reading the change stream from its start time.
+    fn load(&self) -> BoxFuture<'_, Result<Vec<PartitionCheckpoint>, Error>>;
+
+    /// save records that the partition has been handled up to the timestamp of the checkpoint.
+    /// A child partition is saved as soon as it is reported, before its parents are finished.
+    fn save(&self, checkpoint: PartitionCheckpoint) -> BoxFuture<'_, Result<(), Error>>;
+
+    /// finish records that all the records of the partition have been handled, load must not return it anymore.
+    /// A parent that is not returned by load is therefore finished, and does not hold back its children.
+    fn finish(&self, token: String) -> BoxFuture<'_, Result<(), Error>>;
+}
+
+/// InMemoryCheckpointStore keeps the checkpoints in memory, it is lost when the process exits.
+#[derive(Default)]
+pub struct InMemoryCheckpointStore {
+    checkpoints: Mutex<HashMap<String, PartitionCheckpoint>>,
+}
+
+impl InMemoryCheckpointStore {
+    /// checkpoints returns the unfinished partitions.
+    pub fn checkpoints(&self) -> Vec<PartitionCheckpoint> {
+        self.checkpoints.lock().values().cloned().collect()
+    }
+}
+
+impl ChangeStreamCheckpointStore for InMemoryCheckpointStore {
+    fn load(&self) -> BoxFuture<'_, Result<Vec<PartitionCheckpoint>, Error>> {
+        Box::pin(async move { Ok(self.checkpoints()) })
+    }
+
+    fn save(&self, checkpoint: PartitionCheckpoint) -> BoxFuture<'_, Result<(), Error>> {
+        self.checkpoints.lock().insert(checkpoint.token.clone(), checkpoint);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn finish(&self, token: String) -> BoxFuture<'_, Result<(), Error>> {
+        self.checkpoints.lock().remove(&token);
+        Box::pin(async { Ok(()) })
+    }
+}
+
+/// SpannerCheckpointStore keeps the checkpoints in a table of the database like
+/// ```sql
+/// CREATE TABLE ChangeStreamCheckpoint (
+///     partition_token STRING(MAX) NOT NULL,
+///     timestamp TIMESTAMP NOT NULL,
+///     parent_partition_tokens ARRAY<STRING(MAX)> NOT NULL
+/// ) PRIMARY KEY(partition_token);
+/// ```
+///
+/// Each consumer needs its own table, a finished partition is deleted from it.
+#[derive(Clone)]
+pub struct SpannerCheckpointStore {
+    client: Client,
+    table: String,
+}
+
+impl SpannerCheckpointStore {
+    const COLUMNS: [&'static str; 3] = ["partition_token", "timestamp", "parent_partition_tokens"];
+
+    pub fn new(client: Client, checkpoint_table: &str) -> Self {
+        Self {
+            client,
+            table: checkpoint_table.to_string(),
+        }
+    }
+}
+
+impl ChangeStreamCheckpointStore for SpannerCheckpointStore {
+    fn load(&self) -> BoxFuture<'_, Result<Vec<PartitionCheckpoint>, Error>> {
+        Box::pin(async move {
+            let mut tx = self.client.single().await?;
+            let mut iter = tx.read(&self.table, &Self::COLUMNS, all_keys()).await?;
+            let mut checkpoints = vec![];
+            while let Some(row) = iter.next().await? {
+                checkpoints.push(PartitionCheckpoint {
+                    token: row.column_by_name("partition_token")?,
+                    timestamp: row.column_by_name("timestamp")?,
+                    parent_partition_tokens: row.column_by_name("parent_partition_tokens")?,
+                });
+            }
+            Ok(checkpoints)
+        })
+    }
+
+    fn save(&self, checkpoint: PartitionCheckpoint) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            let m = insert_or_update(
+                &self.table,
+                &Self::COLUMNS,
+                &[
+                    &checkpoint.token,
+                    &checkpoint.timestamp,
+                    &checkpoint.parent_partition_tokens,
+                ],
+            );
+            self.client.apply(vec![m]).await?;
+            Ok(())
+        })
+    }
+
+    fn finish(&self, token: String) -> BoxFuture<'_, Result<(), Error>> {
+        Box::pin(async move {
+            self.client.apply(vec![delete(&self.table, Key::new(&token))]).await?;
+            Ok(())
+        })
+    }
+}
+
+/// Partitions schedules the partitions of a change stream.
+/// A child partition becomes ready when all of its parents are finished, so that a merged partition
+/// is read only once and after the records of all the merged partitions.
+#[derive(Default)]
+struct Partitions {
+    ready: VecDeque<PartitionCheckpoint>,
+    /// waiting are the partitions with unfinished parents, in the order they were reported.
+    waiting: Vec<PartitionCheckpoint>,
+    known: HashSet<String>,
+    finished: HashSet<String>,
+}
+
+impl Partitions {
+    /// resume schedules the saved partitions. The ones whose saved parents are unfinished wait for them
+    /// as they did before the restart, the others are ready at once.
+    fn resume(checkpoints: Vec<PartitionCheckpoint>) -> Self {
+        let mut partitions = Self::default();
+        partitions.known.extend(checkpoints.iter().map(|c| c.token.clone()));
+        partitions.waiting = checkpoints;
+        partitions.promote();
+        partitions
+    }
+
+    /// add_children registers the child partitions of the record and returns the ones seen for the first time.
+    fn add_children(&mut self, record: &ChildPartitionsRecord) -> Vec<PartitionCheckpoint> {
+        let mut added = vec![];
+        for child in &record.child_partitions {
+            if !self.known.insert(child.token.clone()) {
+                continue;
+            }
+            let checkpoint = PartitionCheckpoint {
+                token: child.token.clone(),
+                timestamp: record.start_timestamp,
+                parent_partition_tokens: child.parent_partition_tokens.clone(),
+            };
+            added.push(checkpoint.clone());
+            self.waiting.push(checkpoint);
+        }
+        self.promote();
+        added
+    }
+
+    fn finish(&mut self, token: &str) {
+        self.finished.insert(token.to_string());
+        self.promote();
+    }
+
+    fn next(&mut self) -> Option<PartitionCheckpoint> {
+        self.ready.pop_front()
+    }
+
+    /// promote moves the waiting partitions whose parents are all finished to the ready queue.
+    /// Parents that have never been seen, e.g. finished before a resume, do not block their children.
+    fn promote(&mut self) {
+        let (ready, waiting): (Vec<_>, Vec<_>) =
+            std::mem::take(&mut self.waiting).into_iter().partition(|checkpoint| {
+                checkpoint
+                    .parent_partition_tokens
+                    .iter()
+                    .all(|p| self.finished.contains(p) || !self.known.contains(p))
+            });
+        self.waiting = waiting;
+        self.ready.extend(ready);
+    }
+}
+
+/// PartitionEnd is the token of a partition that has been read to its end, with the child partitions it reported.
+type PartitionEnd = (Option<String>, Vec<ChildPartitionsRecord>);
+
+/// ChangeStreamConsumer reads all the partitions of a change stream in parallel.
+///
+/// It starts with the initial partitions of the stream, and follows the partition splits and merges
+/// reported by the child partitions records. At most `num_workers` partitions are read at the same time,
+/// each on its own Tokio task. A checkpoint is saved after every record the handler returns Ok for, and the
+/// consumer resumes from the checkpoints of the store if there are any. The handler is therefore called at least
+/// once per record. An Err from the handler stops the consumer and is returned by run, the record is read again
+/// on resume.
+/// ```
+/// use time::OffsetDateTime;
+/// use google_cloud_spanner::change_stream::{ChangeStreamConsumer, InMemoryCheckpointStore};
+/// use google_cloud_spanner::client::{Client, Error};
+///
+/// async fn run(client: Client) -> Result<(), Error> {
+///     let store = Box::new(InMemoryCheckpointStore::default());
+///     let consumer = ChangeStreamConsumer::new(client, "UserItemChangeStream", OffsetDateTime::now_utc(), 4, store);
+///     consumer
+///         .run(|record| {
+///             Box::pin(async move {
+///                 for change in record.data_change_record {
+///                     println!("{} {}", change.table_name, change.mod_type);
+///                 }
+///                 Ok(())
+///             })
+///         })
+///         .await
+/// }
+/// ```
+pub struct ChangeStreamConsumer {
+    client: Client,
+    stream_name: String,
+    start_time: OffsetDateTime,
+    end_time: Option<OffsetDateTime>,
+    heartbeat_interval: Duration,
+    num_workers: usize,
+    checkpoint_store: Arc<dyn ChangeStreamCheckpointStore>,
+}
+
+impl ChangeStreamConsumer {
+    pub fn new(
+        client: Client,
+        stream_name: &str,
+        start_time: OffsetDateTime,
+        num_workers: usize,
+        checkpoint_store: Box<dyn ChangeStreamCheckpointStore>,
+    ) -> Self {
+        Self {
+            client,
+            stream_name: stream_name.to_string(),
+            start_time,
+            end_time: None,
+            heartbeat_interval: Duration::from_secs(10),
+            num_workers: num_workers.max(1),
+            checkpoint_store: Arc::from(checkpoint_store),
+        }
+    }
+
+    /// with_end_time makes run return once all the records up to the end time are handled.
+    /// Without it, run reads the change stream until an error occurs.
+    pub fn with_end_time(mut self, end_time: OffsetDateTime) -> Self {
+        self.end_time = Some(end_time);
+        self
+    }
+
+    /// with_heartbeat_interval sets how often a partition without changes reports a heartbeat record.
+    pub fn with_heartbeat_interval(mut self, interval: Duration) -> Self {
+        self.heartbeat_interval = interval;
+        self
+    }
+
+    pub async fn run<F, E>(&self, handler: F) -> Result<(), E>
+    where
+        E: From<Error> + Send + 'static,
+        F: Fn(ChangeRecord) -> BoxFuture<'static, Result<(), E>> + Send + Sync + 'static,
+    {
+        if !self.stream_name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(Error::InvalidConfig(format!("invalid change stream name: {}", self.stream_name)).into());
+        }
+        let handler = Arc::new(handler);
+        let checkpoints = self.checkpoint_store.load().await?;
+        let mut running = JoinSet::new();
+        if checkpoints.is_empty() {
+            running.spawn(self.read_partition(None, handler.clone()));
+        }
+        let mut partitions = Partitions::resume(checkpoints);
+        loop {
+            while running.len() < self.num_workers {
+                match partitions.next() {
+                    Some(p) => running.spawn(self.read_partition(Some(p), handler.clone())),
+                    None => break,
+                };
+            }
+            let (token, records) = match running.join_next().await {
+                Some(result) => result.map_err(|e| Error::GRPC(Status::internal(e.to_string())))??,
+                None => return Ok(()),
+            };
+            // The children are saved before the parent is finished so that they are not lost on resume.
+            for record in &records {
+                for child in partitions.add_children(record) {
+                    self.checkpoint_store.save(child).await?;
+                }
+            }
+            if let Some(token) = token {
+                self.checkpoint_store.finish(token.clone()).await?;
+                partitions.finish(&token);
+            }
+        }
+    }
+
+    /// read_partition reads the partition from its checkpoint until it ends and returns its child partitions records.
+    /// The partition None is the root, which is read from the start time and only returns the initial partitions.
+    fn read_partition<F, E>(
+        &self,
+        partition: Option<PartitionCheckpoint>,
+        handler: Arc<F>,
+    ) -> BoxFuture<'static, Result<PartitionEnd, E>>
+    where
+        E: From<Error> + Send + 'static,
+        F: Fn(ChangeRecord) -> BoxFuture<'static, Result<(), E>> + Send + Sync + 'static,
+    {
+        let client = self.client.clone();
+        let store = self.checkpoint_store.clone();
+        let mut stmt = Statement::new(format!(
+            "SELECT ChangeRecord FROM READ_{} (
+                start_timestamp => @start,
+                end_timestamp => @end,
+                partition_token => @token,
+                heartbeat_milliseconds => @heartbeat
+            )",
+            self.stream_name
+        ));
+        let start = partition.as_ref().map_or(self.start_time, |p| p.timestamp);
+        let token = partition.as_ref().map(|p| p.token.clone());
+        stmt.add_param("start", &start);
+        stmt.add_param("end", &self.end_time);
+        stmt.add_param("token", &token);
+        stmt.add_param("heartbeat", &(self.heartbeat_interval.as_millis() as i64));
+        Box::pin(async move {
+            let mut tx = client.single().await?;
+            let option = QueryOptions {
+                enable_resume: false,
+                ..Default::default()
+            };
+            let mut iter = tx.query_with_option(stmt, option).await.map_err(Error::from)?;
+            let mut children = vec![];
+            while let Some(row) = iter.next().await.map_err(Error::from)? {
+                for record in row.column::<Vec<ChangeRecord>>(0).map_err(Error::from)? {
+                    let timestamp = record.timestamp();
+                    children.extend(record.child_partitions_record.iter().cloned());
+                    handler(record).await?;
+                    if let (Some(partition), Some(timestamp)) = (&partition, timestamp) {
+                        store
+                            .save(PartitionCheckpoint {
+                                timestamp,
+                                ..partition.clone()
+                            })
+                            .await?;
+                    }
+                }
+            }
+            Ok((token, children))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use time::macros::datetime;
+
+    use crate::change_stream::{ChildPartition, ChildPartitionsRecord, PartitionCheckpoint, Partitions};
+
+    fn record(children: &[(&str, &[&str])]) -> ChildPartitionsRecord {
+        ChildPartitionsRecord {
+            start_timestamp: datetime!(2024-01-01 0:00 UTC),
+            record_sequence: "00000001".to_string(),
+            child_partitions: children
+                .iter()
+                .map(|(token, parents)| ChildPartition {
+                    token: token.to_string(),
+                    parent_partition_tokens: parents.iter().map(|p| p.to_string()).collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn next_token(partitions: &mut Partitions) -> Option<String> {
+        partitions.next().map(|p| p.token)
+    }
+
+    #[test]
+    fn test_partitions_split_and_merge() {
+        let mut partitions = Partitions::default();
+
+        // initial partitions have no parents
+        assert_eq!(partitions.add_children(&record(&[("a", &[]), ("b", &[])])).len(), 2);
+        assert_eq!(next_token(&mut partitions).as_deref(), Some("a"));
+        assert_eq!(next_token(&mut partitions).as_deref(), Some("b"));
+        assert_eq!(next_token(&mut partitions), None);
+
+        // a splits into c and d
+        assert_eq!(partitions.add_children(&record(&[("c", &["a"]), ("d", &["a"])])).len(), 2);
+        assert_eq!(next_token(&mut partitions), None);
+        partitions.finish("a");
+        let mut split = vec![
+            next_token(&mut partitions).unwrap(),
+            next_token(&mut partitions).unwrap(),
+        ];
+        split.sort();
+        assert_eq!(split, vec!["c", "d"]);
+
+        // b and c merge into e, reported by both parents
+        assert_eq!(partitions.add_children(&record(&[("e", &["b", "c"])])).len(), 1);
+        partitions.finish("b");
+        assert_eq!(next_token(&mut partitions), None);
+        assert!(partitions.add_children(&record(&[("e", &["b", "c"])])).is_empty());
+        partitions.finish("c");
+        assert_eq!(next_token(&mut partitions).as_deref(), Some("e"));
+        assert_eq!(next_token(&mut partitions), None);
+    }
+
+    fn checkpoint(token: &str, parents: &[&str]) -> PartitionCheckpoint {
+        PartitionCheckpoint {
+            token: token.to_string(),
+            timestamp: datetime!(2024-01-02 0:00 UTC),
+            parent_partition_tokens: parents.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_partitions_resume() {
+        let c = checkpoint("c", &["a"]);
+        let mut partitions = Partitions::resume(vec![c.clone()]);
+        assert_eq!(partitions.next(), Some(c));
+
+        // b finished before the resume, so only c is awaited
+        partitions.add_children(&record(&[("e", &["b", "c"])]));
+        assert_eq!(next_token(&mut partitions), None);
+        partitions.finish("c");
+        assert_eq!(next_token(&mut partitions).as_deref(), Some("e"));
+    }
+
+    #[test]
+    fn test_partitions_resume_children_of_unfinished_parents() {
+        // c and d were saved when a split, and e when b and c merged, before the parents finished
+        let mut partitions = Partitions::resume(vec![
+            checkpoint("e", &["b", "c"]),
+            checkpoint("d", &["a"]),
+            checkpoint("c", &["a"]),
+            checkpoint("a", &[]),
+            checkpoint("b", &[]),
+        ]);
+        assert_eq!(next_token(&mut partitions).as_deref(), Some("a"));
+        assert_eq!(next_token(&mut partitions).as_deref(), Some("b"));
+        assert_eq!(next_token(&mut partitions), None);
+
+        partitions.finish("a");
+        assert_eq!(next_token(&mut partitions).as_deref(), Some("d"));
+        assert_eq!(next_token(&mut partitions).as_deref(), Some("c"));
+        partitions.finish("b");
+        assert_eq!(next_token(&mut partitions), None);
+        partitions.finish("c");
+        assert_eq!(next_token(&mut partitions).as_deref(), Some("e"));
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::env::var;
 use std::fmt::Debug;
 use std::future::Future;
@@ -5,14 +6,23 @@ use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 
+use futures_util::stream::{self, Stream};
+use tokio::io::AsyncWrite;
+
 use google_cloud_gax::conn::{ConnectionOptions, Environment};
 use google_cloud_gax::grpc::{Code, Status};
-use google_cloud_gax::retry::{invoke_fn, TryAs};
-use google_cloud_googleapis::spanner::v1::{commit_request, transaction_options, Mutation, TransactionOptions};
+use google_cloud_gax::retry::TryAs;
+use google_cloud_googleapis::spanner::v1::transaction_options::read_write::ReadLockMode;
+use google_cloud_googleapis::spanner::v1::{
+    commit_request, transaction_options, DeleteSessionRequest, ListSessionsRequest, Mutation, Session,
+    TransactionOptions,
+};
 use google_cloud_token::NopeTokenSourceProvider;
 
 use crate::apiv1::conn_pool::{ConnectionManager, SPANNER};
-use crate::retry::TransactionRetrySetting;
+use crate::export::{write_csv, CsvExportOptions};
+use crate::rate_limit::{RateLimitConfig, RateLimiter};
+use crate::retry::{invoke_fn, TransactionRetrySetting};
 use crate::session::{ManagedSession, SessionConfig, SessionError, SessionManager};
 use crate::statement::Statement;
 use crate::transaction::{CallOptions, QueryOptions};
@@ -41,10 +51,34 @@ impl Default for ReadOnlyTransactionOption {
     }
 }
 
+/// ReadWriteTransactionOption configures read_write_transaction_with_option.
+/// Fields are added to it over time, build it from `Default::default()` with the `with_*` setters
+/// so that the code keeps compiling when they are.
 #[derive(Clone, Default)]
 pub struct ReadWriteTransactionOption {
     pub begin_options: CallOptions,
     pub commit_options: CommitOptions,
+    /// read_lock_mode selects when the locks for reads are acquired.
+    /// With Optimistic, reads do not take locks and are instead validated at commit.
+    ///
+    /// The isolation level and exclude_txn_from_change_streams are not available: the TransactionOptions
+    /// of the vendored google.spanner.v1 API do not have those fields yet, so they cannot be sent.
+    pub read_lock_mode: ReadLockMode,
+    /// retry overrides how often and how fast an aborted transaction is retried.
+    /// The default retries ABORTED up to 5 times with exponential backoff.
+    pub retry: Option<TransactionRetrySetting>,
+}
+
+impl ReadWriteTransactionOption {
+    pub fn with_read_lock_mode(mut self, read_lock_mode: ReadLockMode) -> Self {
+        self.read_lock_mode = read_lock_mode;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: TransactionRetrySetting) -> Self {
+        self.retry = Some(retry);
+        self
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -66,6 +100,8 @@ impl Default for ChannelConfig {
 }
 
 /// ClientConfig has configurations for the client.
+/// Build it from `Default::default()` with the `with_*` setters or `..Default::default()`,
+/// since fields are added to it over time.
 #[derive(Debug)]
 pub struct ClientConfig {
     /// SessionPoolConfig is the configuration for session pool.
@@ -73,11 +109,19 @@ pub struct ClientConfig {
     /// ChannelConfig is the configuration for gRPC connection.
     pub channel_config: ChannelConfig,
     /// Overriding service endpoint
+    ///
+    /// Under VPC Service Controls keep the default and resolve `spanner.googleapis.com`
+    /// to the restricted.googleapis.com (or private.googleapis.com) VIP in DNS instead.
+    /// Those hosts route by the requested service name, so dialing them directly does not reach Spanner.
+    /// A Private Service Connect endpoint is set here by its name, e.g. `spanner-myendpoint.p.googleapis.com`.
     pub endpoint: String,
     /// Runtime project
     pub environment: Environment,
+    /// rate_limit limits the transactions started per second, there is no limit by default.
+    pub rate_limit: RateLimitConfig,
 }
 
+/// The default configuration connects to the emulator when SPANNER_EMULATOR_HOST is set.
 impl Default for ClientConfig {
     fn default() -> Self {
         let mut config = ClientConfig {
@@ -88,6 +132,7 @@ impl Default for ClientConfig {
                 Some(v) => Environment::Emulator(v),
                 None => Environment::GoogleCloud(Box::new(NopeTokenSourceProvider {})),
             },
+            rate_limit: RateLimitConfig::default(),
         };
         config.session_config.min_opened = config.channel_config.num_channels * 4;
         config.session_config.max_opened = config.channel_config.num_channels * 100;
@@ -95,6 +140,27 @@ impl Default for ClientConfig {
     }
 }
 
+impl ClientConfig {
+    /// is_emulator returns true when the client connects to the emulator, over plaintext and without authentication.
+    pub fn is_emulator(&self) -> bool {
+        matches!(self.environment, Environment::Emulator(_))
+    }
+
+    /// with_rate_limit limits both the reads and the writes to `qps` transactions per second.
+    /// Use with_rate_limit_config for separate read and write limits.
+    pub fn with_rate_limit(self, qps: f64) -> Self {
+        self.with_rate_limit_config(RateLimitConfig {
+            read_qps: Some(qps),
+            write_qps: Some(qps),
+        })
+    }
+
+    pub fn with_rate_limit_config(mut self, rate_limit: RateLimitConfig) -> Self {
+        self.rate_limit = rate_limit;
+        self
+    }
+}
+
 #[cfg(feature = "auth")]
 pub use google_cloud_auth;
 
@@ -146,6 +212,32 @@ pub enum Error {
 
     #[error("invalid config: {0}")]
     InvalidConfig(String),
+
+    #[error(transparent)]
+    IO(#[from] std::io::Error),
+
+    #[cfg(feature = "json")]
+    #[error("failed to serialize as JSON: {0}")]
+    JsonSerializeError(#[source] serde_json::Error),
+}
+
+/// Converts the error into a gRPC status, e.g. to return it from a tonic service.
+/// gRPC errors keep their original code and message.
+impl From<Error> for Status {
+    fn from(err: Error) -> Self {
+        match err {
+            Error::GRPC(status) => status,
+            Error::InvalidSession(SessionError::GRPC(status)) => status,
+            Error::InvalidSession(e @ SessionError::SessionGetTimeout) => Status::deadline_exceeded(e.to_string()),
+            Error::InvalidSession(e) => Status::unavailable(e.to_string()),
+            Error::ParseError(e) => Status::internal(e.to_string()),
+            Error::Connection(e) => Status::unavailable(e.to_string()),
+            Error::InvalidConfig(e) => Status::invalid_argument(e),
+            Error::IO(e) => Status::internal(e.to_string()),
+            #[cfg(feature = "json")]
+            Error::JsonSerializeError(e) => Status::invalid_argument(e.to_string()),
+        }
+    }
 }
 
 impl TryAs<Status> for Error {
@@ -157,11 +249,44 @@ impl TryAs<Status> for Error {
     }
 }
 
+/// RowCountEstimate is the result of Client::estimate_row_count.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct RowCountEstimate {
+    /// sample_count is the number of rows in the sample.
+    pub sample_count: u64,
+    /// estimated_total is sample_count divided by the sampled fraction of the table.
+    pub estimated_total: u64,
+    /// confidence_95_percent_interval is the normal approximation of the binomial sampling error,
+    /// the lower bound is never less than sample_count.
+    pub confidence_95_percent_interval: (u64, u64),
+}
+
+impl RowCountEstimate {
+    fn new(sample_count: u64, sample_percent: f64) -> Self {
+        let p = sample_percent / 100.0;
+        let k = sample_count as f64;
+        let estimated_total = k / p;
+        let margin = 1.96 * (k * (1.0 - p)).sqrt() / p;
+        Self {
+            sample_count,
+            estimated_total: estimated_total.round() as u64,
+            confidence_95_percent_interval: (
+                (estimated_total - margin).max(k).floor() as u64,
+                (estimated_total + margin).ceil() as u64,
+            ),
+        }
+    }
+}
+
 /// Client is a client for reading and writing data to a Cloud Spanner database.
 /// A client is safe to use concurrently, except for its Close method.
 #[derive(Clone)]
 pub struct Client {
+    database: String,
     sessions: Arc<SessionManager>,
+    emulator: bool,
+    read_limiter: Option<Arc<RateLimiter>>,
+    write_limiter: Option<Arc<RateLimiter>>,
 }
 
 impl Client {
@@ -175,6 +300,13 @@ impl Client {
             )));
         }
 
+        let emulator = config.is_emulator();
+        let read_limiter = RateLimiter::new(config.rate_limit.read_qps)
+            .map_err(Error::InvalidConfig)?
+            .map(Arc::new);
+        let write_limiter = RateLimiter::new(config.rate_limit.write_qps)
+            .map_err(Error::InvalidConfig)?
+            .map(Arc::new);
         let pool_size = config.channel_config.num_channels;
         let options = ConnectionOptions {
             timeout: Some(config.channel_config.timeout),
@@ -182,13 +314,24 @@ impl Client {
         };
         let conn_pool =
             ConnectionManager::new(pool_size, &config.environment, config.endpoint.as_str(), &options).await?;
-        let session_manager = SessionManager::new(database, conn_pool, config.session_config).await?;
+        let database = database.into();
+        let session_manager = SessionManager::new(database.as_str(), conn_pool, config.session_config).await?;
 
         Ok(Client {
+            database,
             sessions: session_manager,
+            emulator,
+            read_limiter,
+            write_limiter,
         })
     }
 
+    /// is_emulator returns true when the client is connected to the emulator.
+    /// See [`ClientConfig::is_emulator`].
+    pub fn is_emulator(&self) -> bool {
+        self.emulator
+    }
+
     /// Close closes all the sessions gracefully.
     /// This method can be called only once.
     pub async fn close(self) {
@@ -220,7 +363,7 @@ impl Client {
     /// where only a single read or query is needed.  This is more efficient than
     /// using read_only_transaction for a single read or query.
     pub async fn single_with_timestamp_bound(&self, tb: TimestampBound) -> Result<ReadOnlyTransaction, Error> {
-        let session = self.get_session().await?;
+        let session = self.get_read_session().await?;
         let result = ReadOnlyTransaction::single(session, tb).await?;
         Ok(result)
     }
@@ -270,7 +413,7 @@ impl Client {
         &self,
         options: ReadOnlyTransactionOption,
     ) -> Result<ReadOnlyTransaction, Error> {
-        let session = self.get_session().await?;
+        let session = self.get_read_session().await?;
         let result = ReadOnlyTransaction::begin(session, options.timestamp_bound, options.call_options).await?;
         Ok(result)
     }
@@ -292,7 +435,7 @@ impl Client {
         &self,
         options: ReadOnlyTransactionOption,
     ) -> Result<BatchReadOnlyTransaction, Error> {
-        let session = self.get_session().await?;
+        let session = self.get_read_session().await?;
         let result = BatchReadOnlyTransaction::begin(session, options.timestamp_bound, options.call_options).await?;
         Ok(result)
     }
@@ -324,11 +467,11 @@ impl Client {
         options: PartitionedUpdateOption,
     ) -> Result<i64, Error> {
         let ro = TransactionRetrySetting::new(vec![Code::Aborted, Code::Internal]);
-        let session = Some(self.get_session().await?);
+        let session = Some(self.get_write_session().await?);
 
         // reuse session
         invoke_fn(
-            Some(ro),
+            ro,
             |session| async {
                 let mut tx =
                     match ReadWriteTransaction::begin_partitioned_dml(session.unwrap(), options.begin_options.clone())
@@ -375,10 +518,10 @@ impl Client {
         options: CommitOptions,
     ) -> Result<Option<Timestamp>, Error> {
         let ro = TransactionRetrySetting::default();
-        let mut session = self.get_session().await?;
+        let mut session = self.get_write_session().await?;
 
         invoke_fn(
-            Some(ro),
+            ro,
             |session| async {
                 let tx = commit_request::Transaction::SingleUseTransaction(TransactionOptions {
                     mode: Some(transaction_options::Mode::ReadWrite(transaction_options::ReadWrite::default())),
@@ -516,15 +659,16 @@ impl Client {
         E: TryAs<Status> + From<SessionError> + From<Status>,
         F: for<'tx> Fn(&'tx mut ReadWriteTransaction) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'tx>>,
     {
-        let (bo, co) = Client::split_read_write_transaction_option(options);
+        let (bo, co, lock_mode, ro) = Client::split_read_write_transaction_option(options);
 
-        let ro = TransactionRetrySetting::default();
-        let session = Some(self.get_session().await?);
+        let session = Some(self.get_write_session().await?);
         // must reuse session
         invoke_fn(
-            Some(ro),
+            ro,
             |session| async {
-                let mut tx = self.create_read_write_transaction::<E>(session, bo.clone()).await?;
+                let mut tx = self
+                    .create_read_write_transaction::<E>(session, bo.clone(), lock_mode)
+                    .await?;
                 let result = f(&mut tx).await;
                 tx.finish(result, Some(co.clone())).await
             },
@@ -575,7 +719,7 @@ impl Client {
     /// }
     /// ```
     pub async fn begin_read_write_transaction(&self) -> Result<ReadWriteTransaction, Error> {
-        let session = self.get_session().await?;
+        let session = self.get_write_session().await?;
         ReadWriteTransaction::begin(session, ReadWriteTransactionOption::default().begin_options)
             .await
             .map_err(|e| e.status.into())
@@ -594,16 +738,17 @@ impl Client {
     where
         E: TryAs<Status> + From<SessionError> + From<Status>,
     {
-        let (bo, co) = Client::split_read_write_transaction_option(options);
+        let (bo, co, lock_mode, ro) = Client::split_read_write_transaction_option(options);
 
-        let ro = TransactionRetrySetting::default();
-        let session = Some(self.get_session().await?);
+        let session = Some(self.get_write_session().await?);
 
         // reuse session
         invoke_fn(
-            Some(ro),
+            ro,
             |session| async {
-                let mut tx = self.create_read_write_transaction::<E>(session, bo.clone()).await?;
+                let mut tx = self
+                    .create_read_write_transaction::<E>(session, bo.clone(), lock_mode)
+                    .await?;
                 let result = f(&mut tx);
                 tx.finish(result, Some(co.clone())).await
             },
@@ -616,20 +761,164 @@ impl Client {
         &self,
         session: Option<ManagedSession>,
         bo: CallOptions,
+        read_lock_mode: ReadLockMode,
     ) -> Result<ReadWriteTransaction, (E, Option<ManagedSession>)>
     where
         E: TryAs<Status> + From<SessionError> + From<Status>,
     {
-        ReadWriteTransaction::begin(session.unwrap(), bo)
+        ReadWriteTransaction::begin_with_read_lock_mode(session.unwrap(), bo, read_lock_mode)
             .await
             .map_err(|e| (E::from(e.status), Some(e.session)))
     }
 
+    /// list_sessions streams the sessions in the database, including the ones created by other clients,
+    /// fetching them page by page.
+    /// See <https://cloud.google.com/spanner/docs/reference/rpc/google.spanner.v1#listsessionsrequest>
+    /// for the syntax of filter.
+    pub fn list_sessions(&self, filter: Option<&str>) -> impl Stream<Item = Result<Session, Error>> + '_ {
+        let filter = filter.unwrap_or_default().to_string();
+        let pages = (VecDeque::new(), Some("".to_string()));
+        stream::try_unfold(pages, move |(mut sessions, mut page_token)| {
+            let filter = filter.clone();
+            async move {
+                if sessions.is_empty() {
+                    if let Some(token) = page_token.take() {
+                        let req = ListSessionsRequest {
+                            database: self.database.clone(),
+                            page_size: 0,
+                            page_token: token,
+                            filter,
+                        };
+                        let mut session = self.get_session().await?;
+                        let response = session.spanner_client.list_sessions(req, None).await?.into_inner();
+                        sessions = response.sessions.into();
+                        if !response.next_page_token.is_empty() {
+                            page_token = Some(response.next_page_token);
+                        }
+                    }
+                }
+                Ok(sessions.pop_front().map(|s| (s, (sessions, page_token))))
+            }
+        })
+    }
+
+    /// delete_session deletes the named session.
+    /// Deleting a session held by the pool of any client makes its next use fail with NotFound,
+    /// after which the pool replaces it.
+    pub async fn delete_session(&self, name: &str) -> Result<(), Error> {
+        let mut session = self.get_session().await?;
+        let req = DeleteSessionRequest { name: name.to_string() };
+        session.spanner_client.delete_session(req, None).await?;
+        Ok(())
+    }
+
+    /// ping runs `SELECT 1` in a single-use transaction and returns the time it took,
+    /// including acquiring a session from the pool. It can back health checks.
+    pub async fn ping(&self) -> Result<Duration, Error> {
+        let started_at = std::time::Instant::now();
+        let mut tx = self.single().await?;
+        let mut iter = tx.query(Statement::new("SELECT 1")).await?;
+        while iter.next().await?.is_some() {}
+        Ok(started_at.elapsed())
+    }
+
+    /// export_csv runs the query in a single-use transaction and writes the result to `writer` as CSV,
+    /// one line per row. It returns the number of rows written, not counting the header.
+    /// ```
+    /// use google_cloud_spanner::client::{Client, Error};
+    /// use google_cloud_spanner::export::CsvExportOptions;
+    /// use google_cloud_spanner::statement::Statement;
+    ///
+    /// async fn run(client: Client) -> Result<String, Error> {
+    ///     let mut csv = Vec::new();
+    ///     let stmt = Statement::new("SELECT UserId, UpdatedAt FROM User");
+    ///     client.export_csv(stmt, &mut csv, CsvExportOptions::default()).await?;
+    ///     Ok(String::from_utf8(csv).unwrap())
+    /// }
+    /// ```
+    pub async fn export_csv(
+        &self,
+        stmt: Statement,
+        writer: impl AsyncWrite + Unpin,
+        options: CsvExportOptions,
+    ) -> Result<u64, Error> {
+        let mut tx = self.single().await?;
+        let mut iter = tx.query(stmt).await?;
+        write_csv(&mut iter, writer, &options).await
+    }
+
+    /// estimate_row_count counts the rows of a Bernoulli sample of `table` and extrapolates the total.
+    /// The table is still scanned in full, the estimate is meant for capacity planning where an exact count is not needed.
+    /// `sample_percent` must be greater than 0 and at most 100.
+    pub async fn estimate_row_count(&self, table: &str, sample_percent: f64) -> Result<RowCountEstimate, Error> {
+        if !(sample_percent > 0.0 && sample_percent <= 100.0) {
+            return Err(Error::InvalidConfig(format!(
+                "sample_percent must be in (0, 100]: {sample_percent}"
+            )));
+        }
+        if table.is_empty() || table.contains('`') {
+            return Err(Error::InvalidConfig(format!("invalid table name: {table}")));
+        }
+        let mut stmt = Statement::new(format!("SELECT COUNT(*) FROM `{table}` TABLESAMPLE BERNOULLI (@pct PERCENT)"));
+        stmt.add_param("pct", &sample_percent);
+        let mut tx = self.single().await?;
+        let mut iter = tx.query(stmt).await?;
+        let sample_count = match iter.next().await? {
+            Some(row) => row.column::<i64>(0)? as u64,
+            None => 0,
+        };
+        Ok(RowCountEstimate::new(sample_count, sample_percent))
+    }
+
+    /// generate_uuid returns a UUID generated by Cloud Spanner's GENERATE_UUID() function.
+    #[cfg(feature = "uuid")]
+    pub async fn generate_uuid(&self) -> Result<uuid::Uuid, Error> {
+        self.generate_uuids(1)
+            .await?
+            .pop()
+            .ok_or_else(|| Error::GRPC(Status::internal("GENERATE_UUID() returned no rows")))
+    }
+
+    /// generate_uuids returns `n` UUIDs generated by Cloud Spanner in a single query.
+    #[cfg(feature = "uuid")]
+    pub async fn generate_uuids(&self, n: usize) -> Result<Vec<uuid::Uuid>, Error> {
+        let mut stmt = Statement::new("SELECT GENERATE_UUID() FROM UNNEST(GENERATE_ARRAY(1, @n))");
+        stmt.add_param("n", &(n as i64));
+        let mut tx = self.single().await?;
+        let mut iter = tx.query(stmt).await?;
+        let mut uuids = Vec::with_capacity(n);
+        while let Some(row) = iter.next().await? {
+            uuids.push(row.column(0)?);
+        }
+        Ok(uuids)
+    }
+
     async fn get_session(&self) -> Result<ManagedSession, SessionError> {
         self.sessions.get().await
     }
 
-    fn split_read_write_transaction_option(options: ReadWriteTransactionOption) -> (CallOptions, CommitOptions) {
-        (options.begin_options, options.commit_options)
+    async fn get_read_session(&self) -> Result<ManagedSession, SessionError> {
+        if let Some(limiter) = &self.read_limiter {
+            limiter.acquire().await;
+        }
+        self.get_session().await
+    }
+
+    async fn get_write_session(&self) -> Result<ManagedSession, SessionError> {
+        if let Some(limiter) = &self.write_limiter {
+            limiter.acquire().await;
+        }
+        self.get_session().await
+    }
+
+    fn split_read_write_transaction_option(
+        options: ReadWriteTransactionOption,
+    ) -> (CallOptions, CommitOptions, ReadLockMode, TransactionRetrySetting) {
+        (
+            options.begin_options,
+            options.commit_options,
+            options.read_lock_mode,
+            options.retry.unwrap_or_default(),
+        )
     }
 }
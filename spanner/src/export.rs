@@ -0,0 +1,132 @@
+use prost_types::value::Kind;
+use prost_types::Value;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::client::Error;
+use crate::reader::{Reader, RowIterator};
+use crate::row::display_value;
+
+#[derive(Clone, Debug)]
+pub struct CsvExportOptions {
+    pub delimiter: char,
+    pub quote: char,
+    /// null_string is written for NULL values. Non-null values equal to it are quoted to stay distinguishable.
+    pub null_string: String,
+    /// header writes the column names as the first line.
+    pub header: bool,
+}
+
+impl Default for CsvExportOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            null_string: "".to_string(),
+            header: true,
+        }
+    }
+}
+
+impl CsvExportOptions {
+    fn line<'a>(&self, fields: impl Iterator<Item = Option<&'a str>>) -> String {
+        let mut line = String::new();
+        for (i, field) in fields.enumerate() {
+            if i > 0 {
+                line.push(self.delimiter);
+            }
+            match field {
+                None => line.push_str(&self.null_string),
+                Some(field) => self.push_field(&mut line, field),
+            }
+        }
+        line.push('\n');
+        line
+    }
+
+    fn push_field(&self, line: &mut String, field: &str) {
+        let needs_quote = field == self.null_string
+            || field
+                .chars()
+                .any(|c| c == self.delimiter || c == self.quote || c == '\n' || c == '\r');
+        if !needs_quote {
+            line.push_str(field);
+            return;
+        }
+        line.push(self.quote);
+        for c in field.chars() {
+            if c == self.quote {
+                line.push(self.quote);
+            }
+            line.push(c);
+        }
+        line.push(self.quote);
+    }
+}
+
+/// Strings are written as is, arrays and structs in the same notation as ColumnDiff::display_patch.
+fn csv_value(value: &Value) -> Option<String> {
+    match &value.kind {
+        None | Some(Kind::NullValue(_)) => None,
+        Some(Kind::StringValue(v)) => Some(v.to_string()),
+        Some(_) => Some(display_value(value)),
+    }
+}
+
+pub(crate) async fn write_csv<T, W>(
+    iter: &mut RowIterator<'_, T>,
+    mut writer: W,
+    options: &CsvExportOptions,
+) -> Result<u64, Error>
+where
+    T: Reader,
+    W: AsyncWrite + Unpin,
+{
+    let first = iter.next().await?;
+    if options.header {
+        let names = iter.columns_metadata().iter().map(|f| Some(f.name.as_str()));
+        writer.write_all(options.line(names).as_bytes()).await?;
+    }
+    let mut count = 0;
+    let mut row = first;
+    while let Some(current) = row {
+        let values: Vec<Option<String>> = current.columns().map(|c| csv_value(c.value)).collect();
+        writer
+            .write_all(options.line(values.iter().map(|v| v.as_deref())).as_bytes())
+            .await?;
+        count += 1;
+        row = iter.next().await?;
+    }
+    writer.flush().await?;
+    Ok(count)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::export::CsvExportOptions;
+
+    #[test]
+    fn test_line() {
+        let options = CsvExportOptions::default();
+        let fields = [
+            Some("1"),
+            Some("a,b"),
+            None,
+            Some(""),
+            Some("say \"hi\""),
+            Some("two\nlines"),
+        ];
+        assert_eq!(
+            options.line(fields.into_iter()),
+            "1,\"a,b\",,\"\",\"say \"\"hi\"\"\",\"two\nlines\"\n"
+        );
+
+        let options = CsvExportOptions {
+            delimiter: '\t',
+            quote: '\'',
+            null_string: "NULL".to_string(),
+            header: false,
+        };
+        let fields = [Some("a,b"), None, Some("NULL"), Some("it's")];
+        assert_eq!(options.line(fields.into_iter()), "a,b\tNULL\t'NULL'\t'it''s'\n");
+    }
+}
@@ -204,6 +204,51 @@ impl KeyRange {
     pub fn new(start: Key, end: Key, kind: RangeKind) -> KeyRange {
         KeyRange { start, end, kind }
     }
+
+    /// closed_closed creates the range `[start, end]`.
+    pub fn closed_closed(start: Key, end: Key) -> KeyRange {
+        KeyRange::new(start, end, RangeKind::ClosedClosed)
+    }
+
+    /// closed_open creates the range `[start, end)`.
+    pub fn closed_open(start: Key, end: Key) -> KeyRange {
+        KeyRange::new(start, end, RangeKind::ClosedOpen)
+    }
+
+    /// open_closed creates the range `(start, end]`.
+    pub fn open_closed(start: Key, end: Key) -> KeyRange {
+        KeyRange::new(start, end, RangeKind::OpenClosed)
+    }
+
+    /// open_open creates the range `(start, end)`.
+    pub fn open_open(start: Key, end: Key) -> KeyRange {
+        KeyRange::new(start, end, RangeKind::OpenOpen)
+    }
+
+    /// all creates an unbounded range. An empty key is a prefix of every key,
+    /// so the closed range between two empty keys covers the whole table or index.
+    pub fn all() -> KeyRange {
+        KeyRange::closed_closed(Key::composite(&[]), Key::composite(&[]))
+    }
+}
+
+impl KeySet {
+    /// add_key adds the key to the set.
+    pub fn add_key(mut self, key: Key) -> KeySet {
+        self.inner.keys.push(key.values);
+        self
+    }
+
+    /// add_range adds the range to the set, so that keys and ranges can be mixed.
+    /// ```
+    /// use google_cloud_spanner::key::{Key, KeyRange, KeySet};
+    ///
+    /// let key_set = KeySet::from(Key::new(&"a")).add_range(KeyRange::closed_open(Key::new(&"m"), Key::new(&"p")));
+    /// ```
+    pub fn add_range(mut self, range: KeyRange) -> KeySet {
+        self.inner.ranges.push(range.into());
+        self
+    }
 }
 
 impl From<KeyRange> for InternalKeyRange {
@@ -361,4 +406,37 @@ mod tests {
             _ => panic!("invalid end key trype"),
         }
     }
+
+    #[test]
+    fn test_key_range_constructors() {
+        let kind = |range: KeyRange| range.kind;
+        assert_eq!(
+            kind(KeyRange::closed_closed(Key::new(&1), Key::new(&2))),
+            RangeKind::ClosedClosed
+        );
+        assert_eq!(kind(KeyRange::closed_open(Key::new(&1), Key::new(&2))), RangeKind::ClosedOpen);
+        assert_eq!(kind(KeyRange::open_closed(Key::new(&1), Key::new(&2))), RangeKind::OpenClosed);
+        assert_eq!(kind(KeyRange::open_open(Key::new(&1), Key::new(&2))), RangeKind::OpenOpen);
+
+        let raw_range: v1::KeyRange = KeyRange::all().into();
+        match (raw_range.start_key_type.unwrap(), raw_range.end_key_type.unwrap()) {
+            (v1::key_range::StartKeyType::StartClosed(start), v1::key_range::EndKeyType::EndClosed(end)) => {
+                assert!(start.values.is_empty());
+                assert!(end.values.is_empty());
+            }
+            _ => panic!("invalid key type"),
+        }
+    }
+
+    #[test]
+    fn test_key_set_add() {
+        let key_set = KeySet::from(Key::new(&1))
+            .add_key(Key::new(&2))
+            .add_range(KeyRange::closed_open(Key::new(&10), Key::new(&20)))
+            .add_range(KeyRange::all());
+        let raw: v1::KeySet = key_set.into();
+        assert_eq!(raw.keys.len(), 2);
+        assert_eq!(raw.ranges.len(), 2);
+        assert!(!raw.all);
+    }
 }
@@ -111,6 +111,18 @@
 //! }
 //! ```
 //!
+//! `with_auth()` and `with_credentials()` leave the configuration untouched when SPANNER_EMULATOR_HOST is set,
+//! so the same code connects to the emulator in tests and to Cloud Spanner with credentials otherwise:
+//!
+//! ```
+//! use google_cloud_spanner::client::{Client, ClientConfig};
+//!
+//! async fn run() {
+//!     let config = ClientConfig::default().with_auth().await.unwrap();
+//!     let client = Client::new("projects/project/instances/instance/databases/database", config).await.unwrap();
+//! }
+//! ```
+//!
 //! ### <a name="Authentication"></a>Authentication
 //!
 //! There are two ways to create a client that is authenticated against the google cloud.
@@ -296,6 +308,40 @@
 //!
 //! You can also construct a Statement directly with a struct literal, providing your own map of parameters.
 //!
+//! STRUCT and ARRAY&lt;STRUCT&gt; parameters are values of a type implementing `ToStruct`,
+//! which also supplies the struct type sent in param_types:
+//!
+//! ```
+//! use google_cloud_spanner::statement::{Kinds, Statement, ToKind, ToStruct, Types};
+//!
+//! pub struct Point {
+//!     pub x: i64,
+//!     pub y: String,
+//! }
+//!
+//! impl ToStruct for Point {
+//!     fn to_kinds(&self) -> Kinds {
+//!         vec![("x", self.x.to_kind()), ("y", self.y.to_kind())]
+//!     }
+//!
+//!     fn get_types() -> Types {
+//!         vec![("x", i64::get_type()), ("y", String::get_type())]
+//!     }
+//! }
+//!
+//! let mut stmt = Statement::new("SELECT x, y FROM UNNEST(@Points)");
+//! stmt.add_param("Points", &vec![Point { x: 1, y: "a".to_string() }]);
+//! ```
+//!
+//! A STRUCT whose fields are only known at runtime can be built with `StructValue` instead:
+//!
+//! ```
+//! use google_cloud_spanner::statement::{Statement, StructValue};
+//!
+//! let mut stmt = Statement::new("SELECT @Point.x, @Point.y");
+//! stmt.add_struct_param("Point", &StructValue::new().field("x", &1_i64).field("y", &"a"));
+//! ```
+//!
 //! Use the Query method to run the statement and obtain an iterator:
 //!
 //! ```
@@ -633,13 +679,23 @@
 //! ```
 pub mod admin;
 pub mod apiv1;
+pub mod change_stream;
 pub mod client;
+pub mod export;
 pub mod key;
+pub mod lock;
+#[cfg(all(feature = "serde", feature = "json"))]
+pub mod log;
 pub mod mutation;
+pub mod pagination;
+pub mod rate_limit;
 pub mod reader;
+pub mod recorder;
 pub mod retry;
 pub mod row;
+pub mod schema;
 pub mod session;
+pub mod sink;
 pub mod statement;
 pub mod transaction;
 pub mod transaction_ro;
@@ -0,0 +1,233 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+
+use google_cloud_gax::grpc::Status;
+
+use crate::client::{Client, Error};
+use crate::statement::Statement;
+
+/// MIN_TTL is the shortest TTL accepted by `try_acquire`, the lock is renewed every half of it.
+pub const MIN_TTL: Duration = Duration::from_secs(1);
+
+/// DistributedLock is a lease based lock stored in a table like
+/// ```sql
+/// CREATE TABLE DistributedLock (
+///     lock_name STRING(MAX) NOT NULL,
+///     owner_id STRING(MAX) NOT NULL,
+///     expires_at TIMESTAMP NOT NULL
+/// ) PRIMARY KEY(lock_name);
+/// ```
+///
+/// A lock is held until its row expires, the expiry is computed with CURRENT_TIMESTAMP() so the clocks
+/// of the clients do not matter. While a LockGuard is alive, a background task renews the lock every
+/// half of the TTL. If a renewal fails, the guard reports the lock as lost and stops renewing it.
+/// ```
+/// use std::time::Duration;
+/// use google_cloud_spanner::client::{Client, Error};
+/// use google_cloud_spanner::lock::DistributedLock;
+///
+/// async fn run(client: Client) -> Result<(), Error> {
+///     let lock = DistributedLock::new(client, "DistributedLock");
+///     if let Some(guard) = lock.try_acquire("leader", Duration::from_secs(10), "worker-1").await? {
+///         // do the exclusive work while guard.is_lost() is false
+///         guard.release().await?;
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct DistributedLock {
+    client: Client,
+    table: String,
+}
+
+impl DistributedLock {
+    pub fn new(client: Client, lock_table: &str) -> Self {
+        Self {
+            client,
+            table: lock_table.to_string(),
+        }
+    }
+
+    /// try_acquire takes the lock if it is free, expired or already owned by `owner_id`.
+    /// It returns None without waiting if another owner holds it.
+    /// `ttl` must be at least `MIN_TTL`.
+    pub async fn try_acquire(
+        &self,
+        lock_name: &str,
+        ttl: Duration,
+        owner_id: &str,
+    ) -> Result<Option<LockGuard>, Error> {
+        if self.table.is_empty() || self.table.contains('`') {
+            return Err(Error::InvalidConfig(format!("invalid lock table name: {}", self.table)));
+        }
+        if ttl < MIN_TTL {
+            return Err(Error::InvalidConfig(format!("lock ttl {ttl:?} is shorter than {MIN_TTL:?}")));
+        }
+        if !self.extend(lock_name, owner_id, ttl, true).await? {
+            return Ok(None);
+        }
+        let lost = Arc::new(AtomicBool::new(false));
+        let renewing = Arc::new(Mutex::new(()));
+        let renewal =
+            self.spawn_renewal(lock_name.to_string(), owner_id.to_string(), ttl, lost.clone(), renewing.clone());
+        Ok(Some(LockGuard {
+            lock: self.clone(),
+            lock_name: lock_name.to_string(),
+            owner_id: owner_id.to_string(),
+            ttl,
+            lost,
+            renewing,
+            renewal,
+        }))
+    }
+
+    /// renew extends the lock of the guard by its TTL from now.
+    /// It fails with FailedPrecondition if the lock has been taken over by another owner.
+    pub async fn renew(&self, guard: &LockGuard) -> Result<(), Error> {
+        if self.extend(&guard.lock_name, &guard.owner_id, guard.ttl, false).await? {
+            Ok(())
+        } else {
+            guard.lost.store(true, Ordering::SeqCst);
+            Err(Error::GRPC(Status::failed_precondition(format!(
+                "lock {} is no longer owned by {}",
+                guard.lock_name, guard.owner_id
+            ))))
+        }
+    }
+
+    fn spawn_renewal(
+        &self,
+        lock_name: String,
+        owner_id: String,
+        ttl: Duration,
+        lost: Arc<AtomicBool>,
+        renewing: Arc<Mutex<()>>,
+    ) -> JoinHandle<()> {
+        let lock = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(ttl / 2).await;
+                let _renewing = renewing.lock().await;
+                match lock.extend(&lock_name, &owner_id, ttl, false).await {
+                    Ok(true) => continue,
+                    Ok(false) => tracing::warn!("lock {} is no longer owned by {}", lock_name, owner_id),
+                    Err(e) => tracing::error!("failed to renew lock {}: {:?}", lock_name, e),
+                }
+                lost.store(true, Ordering::SeqCst);
+                return;
+            }
+        })
+    }
+
+    /// extend sets the expiry of the lock to now + ttl if `owner_id` owns it.
+    /// With `acquire` it also takes over a missing or expired lock.
+    async fn extend(&self, lock_name: &str, owner_id: &str, ttl: Duration, acquire: bool) -> Result<bool, Error> {
+        let (_, extended) = self
+            .client
+            .read_write_transaction(|tx| {
+                let table = self.table.clone();
+                let lock_name = lock_name.to_string();
+                let owner_id = owner_id.to_string();
+                Box::pin(async move {
+                    let mut stmt = Statement::new(format!(
+                        "SELECT owner_id, expires_at > CURRENT_TIMESTAMP() FROM `{table}` WHERE lock_name = @name"
+                    ));
+                    stmt.add_param("name", &lock_name);
+                    let current = {
+                        let mut iter = tx.query(stmt).await?;
+                        match iter.next().await? {
+                            Some(row) => Some((row.column::<String>(0)?, row.column::<bool>(1)?)),
+                            None => None,
+                        }
+                    };
+                    let sql = match current {
+                        None if acquire => {
+                            "INSERT INTO `{table}` (lock_name, owner_id, expires_at) \
+                             VALUES (@name, @owner, TIMESTAMP_ADD(CURRENT_TIMESTAMP(), INTERVAL @ttl MILLISECOND))"
+                        }
+                        Some((owner, alive)) if owner == owner_id || (acquire && !alive) => {
+                            "UPDATE `{table}` SET owner_id = @owner, \
+                             expires_at = TIMESTAMP_ADD(CURRENT_TIMESTAMP(), INTERVAL @ttl MILLISECOND) \
+                             WHERE lock_name = @name"
+                        }
+                        _ => return Ok::<bool, Error>(false),
+                    };
+                    let mut stmt = Statement::new(sql.replace("{table}", &table));
+                    stmt.add_param("name", &lock_name);
+                    stmt.add_param("owner", &owner_id);
+                    stmt.add_param("ttl", &(ttl.as_millis() as i64));
+                    tx.update(stmt).await?;
+                    Ok(true)
+                })
+            })
+            .await?;
+        Ok(extended)
+    }
+
+    async fn delete(&self, lock_name: &str, owner_id: &str) -> Result<(), Error> {
+        self.client
+            .read_write_transaction(|tx| {
+                let mut stmt = Statement::new(format!(
+                    "DELETE FROM `{}` WHERE lock_name = @name AND owner_id = @owner",
+                    self.table
+                ));
+                stmt.add_param("name", &lock_name);
+                stmt.add_param("owner", &owner_id);
+                Box::pin(async move {
+                    tx.update(stmt).await?;
+                    Ok::<(), Error>(())
+                })
+            })
+            .await?;
+        Ok(())
+    }
+}
+
+/// LockGuard is a held lock. Dropping it stops the renewal, the lock is then freed when it expires.
+/// Call `release` to free it immediately.
+pub struct LockGuard {
+    lock: DistributedLock,
+    lock_name: String,
+    owner_id: String,
+    ttl: Duration,
+    lost: Arc<AtomicBool>,
+    /// renewing is held by the background task while it extends the lock.
+    renewing: Arc<Mutex<()>>,
+    renewal: JoinHandle<()>,
+}
+
+impl LockGuard {
+    pub fn lock_name(&self) -> &str {
+        &self.lock_name
+    }
+
+    pub fn owner_id(&self) -> &str {
+        &self.owner_id
+    }
+
+    /// is_lost returns true once a renewal found the lock expired and taken over, or failed.
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::SeqCst)
+    }
+
+    /// release deletes the lock row, unless another owner has taken the lock over in the meantime.
+    /// It waits for a renewal in progress, so the renewal transaction is not abandoned before its commit.
+    pub async fn release(self) -> Result<(), Error> {
+        {
+            let _renewing = self.renewing.lock().await;
+            self.renewal.abort();
+        }
+        self.lock.delete(&self.lock_name, &self.owner_id).await
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        self.renewal.abort();
+    }
+}
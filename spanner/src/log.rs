@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+use futures_util::stream::{self, Stream};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::client::{Client, Error};
+use crate::mutation::insert;
+use crate::row::Error as RowError;
+use crate::statement::Statement;
+use crate::transaction::Transaction;
+use crate::value::CommitTimestamp;
+
+/// SequenceNumber is the position of an entry in the log, starting at 1.
+pub type SequenceNumber = i64;
+
+pub struct LogEntry<T> {
+    pub sequence_number: SequenceNumber,
+    pub entry: T,
+    /// timestamp is the commit timestamp of the append.
+    pub timestamp: OffsetDateTime,
+}
+
+/// SpannerLog is an append-only log of serde serializable entries stored as JSON in a table like
+/// ```sql
+/// CREATE TABLE EventLog (
+///     sequence_number INT64 NOT NULL,
+///     entry BYTES(MAX) NOT NULL,
+///     timestamp TIMESTAMP NOT NULL OPTIONS (allow_commit_timestamp=true)
+/// ) PRIMARY KEY(sequence_number);
+/// ```
+///
+/// append reads the latest sequence number and inserts the next one in the same read-write
+/// transaction, so concurrent appends are serialized by Cloud Spanner and the sequence has no gaps.
+/// It is meant for outbox style logs, every append contends on the last row of the table.
+/// ```
+/// use google_cloud_spanner::client::{Client, Error};
+/// use google_cloud_spanner::log::SpannerLog;
+/// use futures_util::TryStreamExt;
+///
+/// async fn run(client: Client) -> Result<(), Error> {
+///     let log = SpannerLog::<String>::new(client, "EventLog");
+///     let sequence_number = log.append(&"user created".to_string()).await?;
+///     let entries: Vec<_> = log.read_from(sequence_number).try_collect().await?;
+///     println!("{}", entries[0].entry);
+///     Ok(())
+/// }
+/// ```
+pub struct SpannerLog<T> {
+    client: Client,
+    table: String,
+    page_size: i64,
+    _entry: PhantomData<fn() -> T>,
+}
+
+impl<T> SpannerLog<T>
+where
+    T: Serialize + DeserializeOwned,
+{
+    pub fn new(client: Client, table: &str) -> Self {
+        Self {
+            client,
+            table: table.to_string(),
+            page_size: 1000,
+            _entry: PhantomData,
+        }
+    }
+
+    /// with_page_size sets the number of entries read_from fetches per query.
+    pub fn with_page_size(mut self, page_size: i64) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// append stores the entry and returns its sequence number.
+    pub async fn append(&self, entry: &T) -> Result<SequenceNumber, Error> {
+        let bytes = serde_json::to_vec(entry).map_err(Error::JsonSerializeError)?;
+        let (_, sequence_number) = self
+            .client
+            .read_write_transaction(|tx| {
+                let table = self.table.clone();
+                let bytes = bytes.clone();
+                Box::pin(async move {
+                    let sequence_number = latest_sequence(tx, &table).await? + 1;
+                    tx.buffer_write(vec![insert(
+                        &table,
+                        &["sequence_number", "entry", "timestamp"],
+                        &[&sequence_number, &bytes, &CommitTimestamp::new()],
+                    )]);
+                    Ok::<SequenceNumber, Error>(sequence_number)
+                })
+            })
+            .await?;
+        Ok(sequence_number)
+    }
+
+    /// latest_sequence returns the sequence number of the last entry, or 0 if the log is empty.
+    pub async fn latest_sequence(&self) -> Result<SequenceNumber, Error> {
+        let mut tx = self.client.single().await?;
+        latest_sequence(&mut tx, &self.table).await
+    }
+
+    /// read_from streams the entries from `from` on in sequence order, page by page.
+    /// The stream ends at the last entry committed when the last page was read; it does not wait for new entries.
+    pub fn read_from(&self, from: SequenceNumber) -> impl Stream<Item = Result<LogEntry<T>, Error>> + '_ {
+        let pages = (VecDeque::new(), from, false);
+        stream::try_unfold(pages, move |(mut entries, mut next, mut exhausted)| async move {
+            if entries.is_empty() && !exhausted {
+                entries = self.read_page(next).await?;
+                exhausted = (entries.len() as i64) < self.page_size;
+                if let Some(last) = entries.back() {
+                    next = last.sequence_number + 1;
+                }
+            }
+            Ok(entries.pop_front().map(|entry| (entry, (entries, next, exhausted))))
+        })
+    }
+
+    async fn read_page(&self, from: SequenceNumber) -> Result<VecDeque<LogEntry<T>>, Error> {
+        let mut stmt = Statement::new(format!(
+            "SELECT sequence_number, entry, timestamp FROM `{}` WHERE sequence_number >= @from \
+             ORDER BY sequence_number LIMIT @limit",
+            self.table
+        ));
+        stmt.add_param("from", &from);
+        stmt.add_param("limit", &self.page_size);
+        let mut tx = self.client.single().await?;
+        let mut iter = tx.query(stmt).await?;
+        let mut entries = VecDeque::new();
+        while let Some(row) = iter.next().await? {
+            let bytes: Vec<u8> = row.column(1)?;
+            let entry = serde_json::from_slice(&bytes).map_err(|e| RowError::JsonParseError("entry".to_string(), e))?;
+            entries.push_back(LogEntry {
+                sequence_number: row.column(0)?,
+                entry,
+                timestamp: row.column(2)?,
+            });
+        }
+        Ok(entries)
+    }
+}
+
+async fn latest_sequence(tx: &mut Transaction, table: &str) -> Result<SequenceNumber, Error> {
+    let stmt = Statement::new(format!("SELECT MAX(sequence_number) FROM `{table}`"));
+    let mut iter = tx.query(stmt).await?;
+    let latest = match iter.next().await? {
+        Some(row) => row.column::<Option<i64>>(0)?,
+        None => None,
+    };
+    Ok(latest.unwrap_or_default())
+}
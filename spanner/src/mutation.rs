@@ -1,10 +1,11 @@
+use prost::{DecodeError, Message};
 use prost_types::{ListValue, Value};
 
 use google_cloud_googleapis::spanner::v1::mutation::{Delete, Operation, Write};
 use google_cloud_googleapis::spanner::v1::Mutation;
 
 use crate::key::KeySet;
-use crate::statement::{ToKind, ToStruct};
+use crate::statement::{struct_values, ToKind, ToStruct};
 
 fn write(table: &str, columns: &[&str], values: &[&dyn ToKind]) -> Write {
     let values = values
@@ -52,6 +53,22 @@ fn write_struct(table: &str, to_struct: impl ToStruct) -> Write {
     }
 }
 
+fn write_structs<T: ToStruct>(table: &str, rows: impl IntoIterator<Item = T>) -> Write {
+    // every row shares the column list, so the values follow the order of get_types.
+    let columns: Vec<&str> = T::get_types().into_iter().map(|x| x.0).collect();
+    let values = rows
+        .into_iter()
+        .map(|row| ListValue {
+            values: struct_values(&row),
+        })
+        .collect();
+    Write {
+        table: table.to_string(),
+        columns: columns.iter().map(|x| x.to_string()).collect(),
+        values,
+    }
+}
+
 /// Insert returns a Mutation to insert a row into a table. If the row already
 /// exists, the write or transaction fails with codes.AlreadyExists.
 pub fn insert(table: &str, columns: &[&str], values: &[&dyn ToKind]) -> Mutation {
@@ -78,6 +95,15 @@ pub fn insert_struct(table: &str, to_struct: impl ToStruct) -> Mutation {
     }
 }
 
+/// insert_structs returns a single Mutation to insert multiple rows into a table,
+/// specified by Rust structs. If any of the rows already exists, the write or
+/// transaction fails with codes.AlreadyExists.
+pub fn insert_structs<T: ToStruct>(table: &str, rows: impl IntoIterator<Item = T>) -> Mutation {
+    Mutation {
+        operation: Some(Operation::Insert(write_structs(table, rows))),
+    }
+}
+
 /// update returns a Mutation to update a row in a table. If the row does not
 /// already exist, the write or transaction fails.
 pub fn update(table: &str, columns: &[&str], values: &[&dyn ToKind]) -> Mutation {
@@ -103,6 +129,15 @@ pub fn update_struct(table: &str, to_struct: impl ToStruct) -> Mutation {
     }
 }
 
+/// update_structs returns a single Mutation to update multiple rows in a table,
+/// specified by Rust structs. If any of the rows does not already exist, the write
+/// or transaction fails.
+pub fn update_structs<T: ToStruct>(table: &str, rows: impl IntoIterator<Item = T>) -> Mutation {
+    Mutation {
+        operation: Some(Operation::Update(write_structs(table, rows))),
+    }
+}
+
 /// replace returns a Mutation to insert a row into a table, deleting any
 /// existing row. Unlike InsertOrUpdate, this means any values not explicitly
 /// written become NULL.
@@ -134,6 +169,14 @@ pub fn replace_struct(table: &str, to_struct: impl ToStruct) -> Mutation {
     }
 }
 
+/// replace_structs returns a single Mutation to insert multiple rows into a table,
+/// deleting any existing rows. The rows are specified by Rust structs.
+pub fn replace_structs<T: ToStruct>(table: &str, rows: impl IntoIterator<Item = T>) -> Mutation {
+    Mutation {
+        operation: Some(Operation::Replace(write_structs(table, rows))),
+    }
+}
+
 /// insert_or_update returns a Mutation to insert a row into a table. If the row
 /// already exists, it updates it instead. Any column values not explicitly
 /// written are preserved.
@@ -166,6 +209,14 @@ pub fn insert_or_update_struct(table: &str, to_struct: impl ToStruct) -> Mutatio
     }
 }
 
+/// insert_or_update_structs returns a single Mutation to insert multiple rows into a table,
+/// specified by Rust structs. The rows which already exist are updated instead.
+pub fn insert_or_update_structs<T: ToStruct>(table: &str, rows: impl IntoIterator<Item = T>) -> Mutation {
+    Mutation {
+        operation: Some(Operation::InsertOrUpdate(write_structs(table, rows))),
+    }
+}
+
 /// delete removes the rows described by the KeySet from the table. It succeeds
 /// whether or not the keys were present.
 pub fn delete(table: &str, key_set: impl Into<KeySet>) -> Mutation {
@@ -177,6 +228,119 @@ pub fn delete(table: &str, key_set: impl Into<KeySet>) -> Mutation {
     }
 }
 
+/// WriteBuilder builds a Mutation one column at a time, so that each column is
+/// always paired with its value.
+///
+/// ```
+/// use google_cloud_spanner::mutation::WriteBuilder;
+///
+/// const USER: WriteBuilder<'static> = WriteBuilder::into_table("User");
+///
+/// let mutation = USER.set("UserId", "user1").set("Premium", true).insert_or_update();
+/// ```
+#[derive(Clone, Debug)]
+pub struct WriteBuilder<'a> {
+    table: &'a str,
+    columns: Vec<String>,
+    values: Vec<Value>,
+}
+
+impl<'a> WriteBuilder<'a> {
+    pub const fn into_table(table: &'a str) -> Self {
+        Self {
+            table,
+            columns: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// set adds the column and its value to the write.
+    pub fn set(mut self, column: &str, value: impl ToKind) -> Self {
+        self.columns.push(column.to_string());
+        self.values.push(Value {
+            kind: Some(value.to_kind()),
+        });
+        self
+    }
+
+    /// set_if_not_null adds the column only when a value is given, so that an update
+    /// leaves the column unchanged otherwise. Use `set` with `None` to write a NULL.
+    pub fn set_if_not_null<T: ToKind>(self, column: &str, value: Option<T>) -> Self {
+        match value {
+            Some(value) => self.set(column, value),
+            None => self,
+        }
+    }
+
+    fn build(self) -> Write {
+        Write {
+            table: self.table.to_string(),
+            columns: self.columns,
+            values: vec![ListValue { values: self.values }],
+        }
+    }
+
+    /// insert returns a Mutation to insert the row. See [`insert`].
+    pub fn insert(self) -> Mutation {
+        Mutation {
+            operation: Some(Operation::Insert(self.build())),
+        }
+    }
+
+    /// update returns a Mutation to update the row. See [`update`].
+    pub fn update(self) -> Mutation {
+        Mutation {
+            operation: Some(Operation::Update(self.build())),
+        }
+    }
+
+    /// replace returns a Mutation to replace the row. See [`replace`].
+    pub fn replace(self) -> Mutation {
+        Mutation {
+            operation: Some(Operation::Replace(self.build())),
+        }
+    }
+
+    /// insert_or_update returns a Mutation to insert or update the row. See [`insert_or_update`].
+    pub fn insert_or_update(self) -> Mutation {
+        Mutation {
+            operation: Some(Operation::InsertOrUpdate(self.build())),
+        }
+    }
+}
+
+/// MutationEncoder serializes mutations with their protobuf encoding, so that they can be
+/// queued or stored and applied later, e.g. by another service.
+/// ```
+/// use google_cloud_spanner::mutation::{insert, MutationEncoder};
+///
+/// let data = MutationEncoder::encode(&[insert("Guild", &["GuildId"], &[&"guild1"])]);
+/// let mutations = MutationEncoder::decode(&data).unwrap();
+/// assert_eq!(mutations.len(), 1);
+/// ```
+pub struct MutationEncoder;
+
+impl MutationEncoder {
+    /// encode writes each mutation length-delimited, in order.
+    pub fn encode(mutations: &[Mutation]) -> Vec<u8> {
+        let len = mutations.iter().map(|m| m.encoded_len() + 10).sum();
+        let mut buf = Vec::with_capacity(len);
+        for mutation in mutations {
+            // writing to a Vec can't run out of capacity
+            mutation.encode_length_delimited(&mut buf).unwrap();
+        }
+        buf
+    }
+
+    pub fn decode(mut data: &[u8]) -> Result<Vec<Mutation>, DecodeError> {
+        let mut mutations = vec![];
+        while !data.is_empty() {
+            mutations.push(Mutation::decode_length_delimited(&mut data)?);
+        }
+        Ok(mutations)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use prost_types::value::Kind;
@@ -270,6 +434,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_insert_structs() {
+        let rows = vec![
+            TestStruct {
+                struct_field: "abc".to_string(),
+            },
+            TestStruct {
+                struct_field: "def".to_string(),
+            },
+        ];
+        let mutation = insert_structs("Guild", rows);
+        match mutation.operation.unwrap() {
+            v1::mutation::Operation::Insert(mut w) => {
+                assert_eq!("Guild", w.table);
+                assert_eq!(vec!["StructField"], w.columns);
+                assert_eq!(2, w.values.len());
+                assert_eq!(
+                    "def",
+                    match w.values.pop().unwrap().values.pop().unwrap().kind.unwrap() {
+                        Kind::StringValue(v) => v,
+                        _ => panic!("error"),
+                    }
+                );
+            }
+            _ => panic!("invalid operation"),
+        }
+    }
+
     #[test]
     fn test_update() {
         let mutation = update(
@@ -375,6 +567,54 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_write_builder() {
+        const GUILD: WriteBuilder<'static> = WriteBuilder::into_table("Guild");
+        let mutation = GUILD
+            .set("GuildId", "1")
+            .set("UserId", 2_i64)
+            .set("UpdatedAt", CommitTimestamp::new())
+            .insert_or_update();
+        match mutation.operation.unwrap() {
+            v1::mutation::Operation::InsertOrUpdate(mut w) => {
+                assert_eq!("Guild", w.table);
+                assert_eq!(vec!["GuildId", "UserId", "UpdatedAt"], w.columns);
+                assert_eq!(3, w.values.pop().unwrap().values.len());
+            }
+            _ => panic!("invalid operation"),
+        }
+
+        let mutation = GUILD
+            .set("GuildId", "1")
+            .set_if_not_null("UserId", None::<i64>)
+            .set_if_not_null("OwnerUserId", Some("user1"))
+            .set("UpdatedAt", None::<String>)
+            .update();
+        match mutation.operation.unwrap() {
+            v1::mutation::Operation::Update(w) => {
+                assert_eq!(vec!["GuildId", "OwnerUserId", "UpdatedAt"], w.columns);
+                assert_eq!(3, w.values[0].values.len());
+            }
+            _ => panic!("invalid operation"),
+        }
+    }
+
+    #[test]
+    fn test_mutation_encoder() {
+        let mutations = vec![
+            insert("Guild", &["GuildId", "UpdatedAt"], &[&"1", &CommitTimestamp::new()]),
+            WriteBuilder::into_table("User")
+                .set("UserId", "user1")
+                .set("NullableString", None::<String>)
+                .insert_or_update(),
+            delete("Guild", KeySet::from(Key::new(&"1")).add_key(Key::new(&"2"))),
+        ];
+        let data = MutationEncoder::encode(&mutations);
+        assert_eq!(MutationEncoder::decode(&data).unwrap(), mutations);
+        assert!(MutationEncoder::decode(&[]).unwrap().is_empty());
+        assert!(MutationEncoder::decode(&data[..data.len() - 1]).is_err());
+    }
+
     #[test]
     fn test_delete() {
         let mutation = delete("Guild", all_keys());
@@ -0,0 +1,111 @@
+use prost_types::ListValue;
+
+use google_cloud_gax::grpc::Status;
+
+use crate::key::{Key, KeyRange, RangeKind};
+use crate::row::Row;
+use crate::transaction::{ReadOptions, Transaction};
+
+/// PaginatedReader reads a key range page by page.
+///
+/// Each page starts right after the key of the last row of the previous page,
+/// so no offsets are involved. The first `key_columns` entries of `columns` must be
+/// all the key columns of the table, or of a UNIQUE index set in the ReadOptions, in key order.
+/// The start of the next page excludes every row with the key of the last row, so rows that share
+/// it are skipped if the columns are only a prefix of the key or the index is not unique.
+///
+/// It takes a KeyRange rather than a KeySet, because a page is resumed by moving the start of the
+/// range. Use `KeyRange::all()` to page through the whole table.
+/// ```
+/// use google_cloud_gax::grpc::Status;
+/// use google_cloud_spanner::key::{Key, KeyRange};
+/// use google_cloud_spanner::pagination::PaginatedReader;
+/// use google_cloud_spanner::transaction_ro::ReadOnlyTransaction;
+///
+/// async fn run(tx: &mut ReadOnlyTransaction) -> Result<(), Status> {
+///     let range = KeyRange::closed_open(Key::new(&"a"), Key::new(&"b"));
+///     let mut reader = PaginatedReader::new("User", &["UserId", "Premium"], 1, range, 100);
+///     while let Some(rows) = reader.next_page(tx).await? {
+///         println!("{} users", rows.len());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct PaginatedReader {
+    table: String,
+    columns: Vec<String>,
+    key_columns: usize,
+    range: Option<KeyRange>,
+    options: ReadOptions,
+}
+
+impl PaginatedReader {
+    pub fn new(table: &str, columns: &[&str], key_columns: usize, range: KeyRange, page_size: i64) -> Self {
+        Self::new_with_option(table, columns, key_columns, range, page_size, ReadOptions::default())
+    }
+
+    /// new_with_option overrides the limit of the options with page_size.
+    pub fn new_with_option(
+        table: &str,
+        columns: &[&str],
+        key_columns: usize,
+        range: KeyRange,
+        page_size: i64,
+        options: ReadOptions,
+    ) -> Self {
+        Self {
+            table: table.to_string(),
+            columns: columns.iter().map(|x| x.to_string()).collect(),
+            key_columns,
+            range: Some(range),
+            options: ReadOptions {
+                limit: page_size.max(1),
+                ..options
+            },
+        }
+    }
+
+    /// next_page returns the next page of rows, or None when the range is exhausted.
+    /// Use the same transaction for every page to read a consistent snapshot.
+    pub async fn next_page(&mut self, tx: &mut Transaction) -> Result<Option<Vec<Row>>, Status> {
+        if self.key_columns == 0 || self.key_columns > self.columns.len() {
+            return Err(Status::invalid_argument(format!(
+                "key_columns must be from 1 to the number of columns: {}",
+                self.key_columns
+            )));
+        }
+        let range = match self.range.take() {
+            Some(range) => range,
+            None => return Ok(None),
+        };
+        let columns: Vec<&str> = self.columns.iter().map(|x| x.as_str()).collect();
+        let mut iter = tx
+            .read_with_option(&self.table, &columns, range.clone(), self.options.clone())
+            .await?;
+        let mut rows = Vec::with_capacity(self.options.limit as usize);
+        while let Some(row) = iter.next().await? {
+            rows.push(row);
+        }
+        if rows.len() as i64 == self.options.limit {
+            self.range = rows.last().map(|last| self.remaining(range, last));
+        }
+        if rows.is_empty() {
+            Ok(None)
+        } else {
+            Ok(Some(rows))
+        }
+    }
+
+    fn remaining(&self, range: KeyRange, last: &Row) -> KeyRange {
+        let start = Key {
+            values: ListValue {
+                values: last.columns().take(self.key_columns).map(|c| c.value.clone()).collect(),
+            },
+        };
+        let kind = match range.kind {
+            RangeKind::ClosedClosed | RangeKind::OpenClosed => RangeKind::OpenClosed,
+            RangeKind::ClosedOpen | RangeKind::OpenOpen => RangeKind::OpenOpen,
+        };
+        KeyRange::new(start, range.end, kind)
+    }
+}
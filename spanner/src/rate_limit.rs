@@ -0,0 +1,74 @@
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+/// RateLimitConfig limits how many transactions the client starts per second.
+/// Reads are the single-use, read-only and batch read-only transactions, writes are the read-write
+/// transactions, apply and partitioned updates. A transaction over the limit waits for its turn
+/// instead of failing, which smooths out spikes that would otherwise end in RESOURCE_EXHAUSTED.
+///
+/// The limit is applied when a transaction takes its session from the pool. The attempts retried
+/// after an ABORTED error reuse the session, so they are not limited again.
+/// A qps so small that `1 / qps` seconds cannot be represented is rejected by Client::new.
+#[derive(Clone, Debug, Default)]
+pub struct RateLimitConfig {
+    pub read_qps: Option<f64>,
+    pub write_qps: Option<f64>,
+}
+
+/// RateLimiter spaces the permits evenly at `1 / qps` intervals, without bursts.
+/// It is a small replacement of the governor crate, which is not a dependency of this crate.
+pub(crate) struct RateLimiter {
+    interval: Duration,
+    next: Mutex<Instant>,
+}
+
+impl RateLimiter {
+    /// new returns None for a missing or non positive qps, which means no limit.
+    pub(crate) fn new(qps: Option<f64>) -> Result<Option<Self>, String> {
+        let qps = match qps.filter(|qps| *qps > 0.0) {
+            Some(qps) => qps,
+            None => return Ok(None),
+        };
+        let interval = Duration::try_from_secs_f64(1.0 / qps).map_err(|e| format!("invalid qps {qps}: {e}"))?;
+        Ok(Some(Self {
+            interval,
+            next: Mutex::new(Instant::now()),
+        }))
+    }
+
+    pub(crate) async fn acquire(&self) {
+        let wait = {
+            let mut next = self.next.lock();
+            let now = Instant::now();
+            let slot = (*next).max(now);
+            *next = slot + self.interval;
+            slot - now
+        };
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::rate_limit::RateLimiter;
+
+    #[tokio::test]
+    async fn test_rate_limiter() {
+        assert!(RateLimiter::new(None).unwrap().is_none());
+        assert!(RateLimiter::new(Some(0.0)).unwrap().is_none());
+        assert!(RateLimiter::new(Some(1e-20)).is_err());
+
+        let limiter = RateLimiter::new(Some(50.0)).unwrap().unwrap();
+        let started = Instant::now();
+        for _ in 0..6 {
+            limiter.acquire().await;
+        }
+        // the first permit is immediate, the next five are 20ms apart
+        assert!(started.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -1,15 +1,20 @@
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
+use futures_util::Stream;
 use prost_types::{value::Kind, Value};
+use time::OffsetDateTime;
 
 use google_cloud_gax::grpc::{Code, Response, Status, Streaming};
 use google_cloud_googleapis::spanner::v1::struct_type::Field;
-use google_cloud_googleapis::spanner::v1::{ExecuteSqlRequest, PartialResultSet, ReadRequest, ResultSetMetadata};
+use google_cloud_googleapis::spanner::v1::{
+    ExecuteSqlRequest, PartialResultSet, QueryPlan, ReadRequest, ResultSetMetadata, ResultSetStats,
+};
 
 use crate::row::Row;
 use crate::session::SessionHandle;
 use crate::transaction::CallOptions;
+use crate::value::Timestamp;
 
 pub trait Reader: Send + Sync {
     fn read(
@@ -105,7 +110,11 @@ impl ResultSet {
         match previous_last.kind.unwrap() {
             Kind::StringValue(last) => match current_first.kind.unwrap() {
                 Kind::StringValue(first) => {
-                    tracing::trace!("previous_last={}, current_first={}", &last, first);
+                    tracing::trace!(
+                        "merge chunked string previous_last={}bytes, current_first={}bytes",
+                        last.len(),
+                        first.len()
+                    );
                     Ok(Value {
                         kind: Some(Kind::StringValue(last + &first)),
                     })
@@ -164,6 +173,10 @@ impl ResultSet {
                 self.index = Arc::new(index);
             }
         }
+        // the metadata of an empty result is kept for columns_metadata
+        if values.is_empty() {
+            return Ok(false);
+        }
 
         if self.chunked_value {
             tracing::trace!("now chunked value found previous={}, current={}", self.rows.len(), values.len());
@@ -186,6 +199,8 @@ where
     reader: T,
     rs: ResultSet,
     reader_option: Option<CallOptions>,
+    stats: Option<ResultSetStats>,
+    read_timestamp: Option<OffsetDateTime>,
 }
 
 impl<'a, T> RowIterator<'a, T>
@@ -210,6 +225,8 @@ where
             reader,
             rs,
             reader_option: None,
+            stats: None,
+            read_timestamp: None,
         })
     }
 
@@ -234,8 +251,16 @@ where
 
         match maybe_result_set {
             Some(result_set) => {
-                if result_set.values.is_empty() {
-                    return Ok(false);
+                if result_set.stats.is_some() {
+                    self.stats = result_set.stats;
+                }
+                if let Some(ts) = result_set
+                    .metadata
+                    .as_ref()
+                    .and_then(|m| m.transaction.as_ref())
+                    .and_then(|tx| tx.read_timestamp)
+                {
+                    self.read_timestamp = OffsetDateTime::try_from(Timestamp::from(ts)).ok();
                 }
                 //if resume_token changes set new resume_token
                 if !result_set.resume_token.is_empty() {
@@ -262,6 +287,25 @@ where
         None
     }
 
+    /// stats returns the statistics sent with the last partial result set, once the rows are exhausted.
+    /// Queries return them in QueryMode::Profile (row count, elapsed time and query plan) or
+    /// QueryMode::Plan (query plan only), and DML statements return the modified row count.
+    pub fn stats(&self) -> Option<&ResultSetStats> {
+        self.stats.as_ref()
+    }
+
+    /// query_plan returns the execution plan of a query run in QueryMode::Plan or QueryMode::Profile,
+    /// once the rows are exhausted. In QueryMode::Plan the query is not executed and returns no rows.
+    pub fn query_plan(&self) -> Option<&QueryPlan> {
+        self.stats.as_ref().and_then(|stats| stats.query_plan.as_ref())
+    }
+
+    /// read_timestamp returns the timestamp a single-use read-only transaction read at,
+    /// once the first result has been received.
+    pub fn read_timestamp(&self) -> Option<OffsetDateTime> {
+        self.read_timestamp
+    }
+
     /// next returns the next result.
     /// Its second return value is None if there are no more results.
     pub async fn next(&mut self) -> Result<Option<Row>, Status> {
@@ -276,6 +320,27 @@ where
             }
         }
     }
+
+    /// into_stream yields the rows as they arrive instead of collecting them.
+    /// Dropping the stream before it is exhausted cancels the streaming RPC.
+    /// ```
+    /// use futures_util::TryStreamExt;
+    /// use google_cloud_gax::grpc::Status;
+    /// use google_cloud_spanner::row::Row;
+    /// use google_cloud_spanner::statement::Statement;
+    /// use google_cloud_spanner::transaction_ro::ReadOnlyTransaction;
+    ///
+    /// async fn run(tx: &mut ReadOnlyTransaction) -> Result<Vec<Row>, Status> {
+    ///     let iter = tx.query(Statement::new("SELECT * FROM User")).await?;
+    ///     iter.into_stream().try_collect().await
+    /// }
+    /// ```
+    pub fn into_stream(self) -> impl Stream<Item = Result<Row, Status>> + Send + 'a
+    where
+        T: 'a,
+    {
+        futures_util::stream::try_unfold(self, |mut iter| async move { Ok(iter.next().await?.map(|row| (row, iter))) })
+    }
 }
 
 #[cfg(test)]
@@ -283,6 +348,7 @@ mod tests {
     use std::collections::VecDeque;
     use std::sync::Arc;
 
+    use base64::prelude::*;
     use prost_types::value::Kind;
     use prost_types::Value;
 
@@ -589,6 +655,36 @@ mod tests {
         assert_some_multi_column(rs.next(), "value3".to_string(), "value4".to_string());
     }
 
+    #[test]
+    fn test_rs_add_large_bytes_chunked_value() {
+        let mut rs = empty_rs();
+        let metadata = Some(ResultSetMetadata {
+            row_type: Some(StructType {
+                fields: vec![field("column1"), field("column2")],
+            }),
+            transaction: None,
+            undeclared_parameters: None,
+        });
+
+        // 15MB BYTES value is larger than the 10MB limit of a PartialResultSet.
+        let bytes: Vec<u8> = (0..15 * 1024 * 1024).map(|i| (i % 251) as u8).collect();
+        let encoded = BASE64_STANDARD.encode(&bytes);
+        let (first, rest) = encoded.split_at(8 * 1024 * 1024);
+        let (second, third) = rest.split_at(8 * 1024 * 1024);
+
+        assert!(rs.add(metadata.clone(), vec![value(first)], true).unwrap());
+        assert!(rs.next().is_none());
+        assert!(rs.add(metadata.clone(), vec![value(second)], true).unwrap());
+        assert!(rs.next().is_none());
+        assert!(rs.add(metadata, vec![value(third), value("value2")], false).unwrap());
+        assert_eq!(rs.rows.len(), 2);
+
+        let row = rs.next().unwrap();
+        assert_eq!(bytes, row.column::<Vec<u8>>(0).unwrap());
+        assert_eq!("value2", row.column::<String>(1).unwrap());
+        assert!(rs.next().is_none());
+    }
+
     #[test]
     fn test_rs_add_multi_column_no_chunked_value_list_value() {
         let mut rs = empty_rs();
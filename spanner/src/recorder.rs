@@ -0,0 +1,184 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Instant;
+
+use base64::prelude::*;
+use time::OffsetDateTime;
+use tracing::Level;
+
+use google_cloud_gax::grpc::{Code, Status};
+use google_cloud_gax::retry::TryAs;
+
+use crate::client::{Client, Error};
+use crate::retry::TransactionRetry;
+use crate::transaction_rw::ReadWriteTransaction;
+use crate::value::Timestamp;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TransactionOutcome {
+    Committed,
+    RolledBack,
+    /// Aborted attempts are retried, each of them is recorded.
+    Aborted,
+}
+
+/// TransactionRecord is the event logged for each attempt of a transaction.
+#[derive(Clone, Debug)]
+pub struct TransactionRecord {
+    pub trace_id: String,
+    /// transaction_id is the base64 encoded id assigned by Cloud Spanner.
+    pub transaction_id: String,
+    pub outcome: TransactionOutcome,
+    /// attempt_count starts at 1 and is incremented by each retry of an aborted transaction.
+    pub attempt_count: usize,
+    pub commit_timestamp: Option<OffsetDateTime>,
+    pub mutation_count: usize,
+    /// duration_ms is the time elapsed since the first attempt started.
+    pub duration_ms: u128,
+}
+
+#[derive(Clone, Debug)]
+pub struct RecorderConfig {
+    commit_level: Level,
+    rollback_level: Level,
+    abort_level: Level,
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        Self {
+            commit_level: Level::INFO,
+            rollback_level: Level::WARN,
+            abort_level: Level::WARN,
+        }
+    }
+}
+
+impl RecorderConfig {
+    pub fn log_level_for_commit(mut self, level: Level) -> Self {
+        self.commit_level = level;
+        self
+    }
+
+    pub fn log_level_for_rollback(mut self, level: Level) -> Self {
+        self.rollback_level = level;
+        self
+    }
+
+    pub fn log_level_for_abort(mut self, level: Level) -> Self {
+        self.abort_level = level;
+        self
+    }
+
+    fn level(&self, outcome: TransactionOutcome) -> Level {
+        match outcome {
+            TransactionOutcome::Committed => self.commit_level,
+            TransactionOutcome::RolledBack => self.rollback_level,
+            TransactionOutcome::Aborted => self.abort_level,
+        }
+    }
+}
+
+/// tracing requires the level of an event to be a constant.
+macro_rules! record_at {
+    ($level:expr, $record:expr) => {{
+        let r = $record;
+        match $level {
+            Level::TRACE => record_at!(@event Level::TRACE, r),
+            Level::DEBUG => record_at!(@event Level::DEBUG, r),
+            Level::INFO => record_at!(@event Level::INFO, r),
+            Level::WARN => record_at!(@event Level::WARN, r),
+            Level::ERROR => record_at!(@event Level::ERROR, r),
+        }
+    }};
+    (@event $level:expr, $r:expr) => {
+        tracing::event!(
+            $level,
+            trace_id = %$r.trace_id,
+            transaction_id = %$r.transaction_id,
+            outcome = ?$r.outcome,
+            attempt_count = $r.attempt_count,
+            commit_timestamp = ?$r.commit_timestamp,
+            mutation_count = $r.mutation_count,
+            duration_ms = $r.duration_ms as u64,
+            "spanner transaction"
+        )
+    };
+}
+
+/// TransactionRecorder runs read-write transactions like Client::read_write_transaction and logs a
+/// TransactionRecord for each attempt, at INFO for commits and at WARN for rollbacks and aborts by default.
+/// ```
+/// use google_cloud_spanner::client::{Client, Error};
+/// use google_cloud_spanner::mutation::insert;
+/// use google_cloud_spanner::recorder::{RecorderConfig, TransactionRecorder};
+/// use tracing::Level;
+///
+/// async fn run(client: Client) -> Result<(), Error> {
+///     let recorder = TransactionRecorder::new(client, RecorderConfig::default().log_level_for_commit(Level::DEBUG));
+///     recorder
+///         .read_write_transaction("request-1", |tx| {
+///             Box::pin(async move {
+///                 tx.buffer_write(vec![insert("Guild", &["GuildId", "OwnerUserId"], &[&"guild1", &"user1"])]);
+///                 Ok::<(), Error>(())
+///             })
+///         })
+///         .await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+pub struct TransactionRecorder {
+    client: Client,
+    config: RecorderConfig,
+}
+
+impl TransactionRecorder {
+    pub fn new(client: Client, config: RecorderConfig) -> Self {
+        Self { client, config }
+    }
+
+    /// read_write_transaction calls f in a new read-write transaction and commits it, and retries when
+    /// the transaction is aborted. `trace_id` correlates the records with the caller, e.g. a request id.
+    pub async fn read_write_transaction<T, E, F>(&self, trace_id: &str, f: F) -> Result<(Option<Timestamp>, T), E>
+    where
+        E: TryAs<Status> + From<Status> + From<Error>,
+        F: for<'tx> Fn(&'tx mut ReadWriteTransaction) -> Pin<Box<dyn Future<Output = Result<T, E>> + Send + 'tx>>,
+    {
+        let started = Instant::now();
+        let mut retry = TransactionRetry::new();
+        let mut attempt_count = 0;
+        loop {
+            attempt_count += 1;
+            let mut tx = self.client.begin_read_write_transaction().await?;
+            let transaction_id = BASE64_STANDARD.encode(tx.transaction_id().unwrap_or_default());
+            let result = f(&mut tx).await;
+            let mutation_count = tx.buffered_mutation_count();
+            let result = tx.end(result, None).await;
+            let (outcome, commit_timestamp) = match &result {
+                Ok((commit_timestamp, _)) => (
+                    TransactionOutcome::Committed,
+                    commit_timestamp.clone().and_then(|t| OffsetDateTime::try_from(t).ok()),
+                ),
+                Err(err) if err.try_as().map(|s| s.code()) == Some(Code::Aborted) => {
+                    (TransactionOutcome::Aborted, None)
+                }
+                Err(_) => (TransactionOutcome::RolledBack, None),
+            };
+            let record = TransactionRecord {
+                trace_id: trace_id.to_string(),
+                transaction_id,
+                outcome,
+                attempt_count,
+                commit_timestamp,
+                mutation_count,
+                duration_ms: started.elapsed().as_millis(),
+            };
+            record_at!(self.config.level(outcome), &record);
+            match result {
+                Ok(committed) => return Ok(committed),
+                Err(err) => retry.next(err).await?,
+            }
+        }
+    }
+}
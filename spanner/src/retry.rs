@@ -1,8 +1,36 @@
+use std::future::Future;
 use std::iter::Take;
 use std::marker::PhantomData;
+use std::time::Duration;
+
+use prost::Message;
 
 use google_cloud_gax::grpc::{Code, Status};
 use google_cloud_gax::retry::{CodeCondition, Condition, ExponentialBackoff, Retry, RetrySetting, TryAs};
+use google_cloud_googleapis::rpc::Status as RpcStatus;
+
+pub(crate) const RETRY_INFO_TYPE: &str = "type.googleapis.com/google.rpc.RetryInfo";
+
+/// RetryInfo is google.rpc.RetryInfo, which google-cloud-googleapis does not generate.
+#[derive(Clone, PartialEq, Message)]
+pub(crate) struct RetryInfo {
+    #[prost(message, optional, tag = "1")]
+    pub(crate) retry_delay: Option<prost_types::Duration>,
+}
+
+/// retry_delay returns the delay Cloud Spanner recommends in the RetryInfo detail of an ABORTED status.
+pub(crate) fn retry_delay(status: &Status) -> Option<Duration> {
+    if status.code() != Code::Aborted {
+        return None;
+    }
+    let details = RpcStatus::decode(status.details()).ok()?;
+    details
+        .details
+        .iter()
+        .filter(|detail| detail.type_url == RETRY_INFO_TYPE)
+        .find_map(|detail| RetryInfo::decode(detail.value.as_slice()).ok()?.retry_delay)
+        .and_then(|delay| Duration::try_from(delay).ok())
+}
 
 pub struct TransactionCondition<E>
 where
@@ -41,15 +69,18 @@ where
 {
     strategy: Take<ExponentialBackoff>,
     condition: TransactionCondition<E>,
+    max_delay: Option<Duration>,
 }
 
 impl<E> TransactionRetry<E>
 where
     E: TryAs<Status>,
 {
+    /// next waits before the next attempt, for the delay recommended by Cloud Spanner if the error has one,
+    /// otherwise for the exponential backoff. The recommended delay is capped at the max_delay of the setting.
     pub async fn next(&mut self, status: E) -> Result<(), E> {
         let duration = if self.condition.should_retry(&status) {
-            self.strategy.next()
+            self.strategy.next().map(|backoff| self.delay(&status, backoff))
         } else {
             None
         };
@@ -62,12 +93,23 @@ where
         }
     }
 
+    fn delay(&self, status: &E, backoff: Duration) -> Duration {
+        match status.try_as().and_then(retry_delay) {
+            Some(recommended) => self.max_delay.map_or(recommended, |max| recommended.min(max)),
+            None => backoff,
+        }
+    }
+
     pub fn new() -> Self {
-        let setting = TransactionRetrySetting::default();
+        Self::new_with_setting(TransactionRetrySetting::default())
+    }
+
+    pub fn new_with_setting(setting: TransactionRetrySetting) -> Self {
         let strategy = <TransactionRetrySetting as Retry<E, TransactionCondition<E>>>::strategy(&setting);
         Self {
             strategy,
             condition: setting.condition(),
+            max_delay: setting.inner.max_delay,
         }
     }
 }
@@ -81,6 +123,31 @@ where
     }
 }
 
+/// invoke_fn repeats f like google_cloud_gax::retry::invoke_fn, but waits between the attempts like
+/// TransactionRetry::next so that the delay recommended by Cloud Spanner is honored.
+/// The argument specified by 'v' can be reused for each retry.
+pub(crate) async fn invoke_fn<R, V, A, E>(
+    setting: TransactionRetrySetting,
+    mut f: impl FnMut(V) -> A,
+    mut v: V,
+) -> Result<R, E>
+where
+    E: TryAs<Status>,
+    A: Future<Output = Result<R, (E, V)>>,
+{
+    let mut retry = TransactionRetry::new_with_setting(setting);
+    loop {
+        match f(v).await {
+            Ok(result) => return Ok(result),
+            Err((err, next)) => {
+                v = next;
+                retry.next(err).await?;
+                tracing::trace!("retry fn");
+            }
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TransactionRetrySetting {
     pub inner: RetrySetting,
@@ -121,11 +188,16 @@ impl Default for TransactionRetrySetting {
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
+    use prost::Message;
+
     use google_cloud_gax::grpc::{Code, Status};
     use google_cloud_gax::retry::{Condition, Retry};
+    use google_cloud_googleapis::rpc::Status as RpcStatus;
 
     use crate::client::Error;
-    use crate::retry::TransactionRetrySetting;
+    use crate::retry::{retry_delay, RetryInfo, TransactionRetry, TransactionRetrySetting, RETRY_INFO_TYPE};
 
     #[test]
     fn test_transaction_condition() {
@@ -136,4 +208,57 @@ mod tests {
         let err = &Error::GRPC(Status::new(Code::Aborted, ""));
         assert!(default.condition().should_retry(err));
     }
+
+    fn aborted_with_retry_info(code: Code, delay: prost_types::Duration) -> Status {
+        let retry_info = RetryInfo {
+            retry_delay: Some(delay),
+        };
+        let details = RpcStatus {
+            code: Code::Aborted as i32,
+            message: "aborted".to_string(),
+            details: vec![prost_types::Any {
+                type_url: RETRY_INFO_TYPE.to_string(),
+                value: retry_info.encode_to_vec(),
+            }],
+        }
+        .encode_to_vec();
+        Status::with_details(code, "aborted", details.into())
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        let delay = prost_types::Duration {
+            seconds: 0,
+            nanos: 25_000_000,
+        };
+        let status = aborted_with_retry_info(Code::Aborted, delay);
+        assert_eq!(retry_delay(&status), Some(Duration::from_millis(25)));
+
+        let status = aborted_with_retry_info(Code::Unavailable, delay);
+        assert_eq!(retry_delay(&status), None);
+        assert_eq!(retry_delay(&Status::new(Code::Aborted, "aborted")), None);
+    }
+
+    #[test]
+    fn test_retry_delay_capped_at_max_delay() {
+        let delay = prost_types::Duration { seconds: 60, nanos: 0 };
+        let err = Error::GRPC(aborted_with_retry_info(Code::Aborted, delay));
+        let backoff = Duration::from_millis(10);
+
+        let retry = TransactionRetry::<Error>::new();
+        assert_eq!(retry.delay(&err, backoff), Duration::from_secs(1));
+
+        let mut setting = TransactionRetrySetting::default();
+        setting.inner.max_delay = Some(Duration::from_secs(5));
+        let retry = TransactionRetry::<Error>::new_with_setting(setting);
+        assert_eq!(retry.delay(&err, backoff), Duration::from_secs(5));
+
+        setting = TransactionRetrySetting::default();
+        setting.inner.max_delay = None;
+        let retry = TransactionRetry::<Error>::new_with_setting(setting);
+        assert_eq!(retry.delay(&err, backoff), Duration::from_secs(60));
+
+        let err = Error::GRPC(Status::new(Code::Aborted, "aborted"));
+        assert_eq!(retry.delay(&err, backoff), backoff);
+    }
 }
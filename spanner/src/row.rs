@@ -1,4 +1,7 @@
+use std::collections::hash_map::DefaultHasher;
 use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
 use std::num::ParseIntError;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -12,7 +15,7 @@ use time::macros::format_description;
 use time::{Date, OffsetDateTime};
 
 use google_cloud_googleapis::spanner::v1::struct_type::Field;
-use google_cloud_googleapis::spanner::v1::StructType;
+use google_cloud_googleapis::spanner::v1::{StructType, Type, TypeCode};
 
 use crate::bigdecimal::{BigDecimal, ParseBigDecimalError};
 use crate::value::CommitTimestamp;
@@ -52,6 +55,19 @@ pub enum Error {
     BigDecimalParseError(String, #[source] ParseBigDecimalError),
     #[error("Failed to parse as Prost Timestamp field={0}")]
     ProstTimestampParseError(String, #[source] ::prost_types::TimestampError),
+    #[error("Failed to parse as network address field={0}")]
+    AddrParseError(String, #[source] std::net::AddrParseError),
+    #[cfg(feature = "uuid")]
+    #[error("Failed to parse as Uuid field={0}")]
+    UuidParseError(String, #[source] uuid::Error),
+    #[cfg(feature = "chrono")]
+    #[error("Failed to parse as chrono DateTime field={0}")]
+    ChronoParseError(String, #[source] chrono::ParseError),
+    #[cfg(feature = "json")]
+    #[error("Failed to parse as JSON field={0}")]
+    JsonParseError(String, #[source] serde_json::Error),
+    #[error("Conflicting column value: name={0}")]
+    ConflictingColumn(String),
 }
 
 impl Row {
@@ -72,6 +88,296 @@ impl Row {
     {
         self.column(index(&self.index, column_name)?)
     }
+
+    /// columns_metadata returns the name and type of each column in the row.
+    pub fn columns_metadata(&self) -> &Arc<Vec<Field>> {
+        &self.fields
+    }
+
+    /// column_names returns the names of the columns in the order they were selected.
+    pub fn column_names(&self) -> Vec<&str> {
+        self.fields.iter().map(|f| f.name.as_str()).collect()
+    }
+
+    /// column_index returns the position of the named column, if present.
+    pub fn column_index(&self, column_name: &str) -> Option<usize> {
+        self.index.get(column_name).copied()
+    }
+
+    /// columns iterates all the columns in the row in the order they were selected.
+    pub fn columns(&self) -> impl Iterator<Item = ColumnValue<'_>> {
+        self.fields
+            .iter()
+            .zip(self.values.iter())
+            .map(|(field, value)| ColumnValue {
+                name: &field.name,
+                field,
+                value,
+            })
+    }
+
+    /// select returns a new row holding only the named columns, in the given order.
+    pub fn select(&self, columns: &[&str]) -> Result<Row, Error> {
+        let positions = columns
+            .iter()
+            .map(|name| index(&self.index, name))
+            .collect::<Result<Vec<usize>, Error>>()?;
+        Ok(self.project(positions))
+    }
+
+    /// exclude returns a new row holding all the columns except the named ones.
+    pub fn exclude(&self, columns: &[&str]) -> Result<Row, Error> {
+        let excluded = columns
+            .iter()
+            .map(|name| index(&self.index, name))
+            .collect::<Result<Vec<usize>, Error>>()?;
+        Ok(self.project((0..self.fields.len()).filter(|i| !excluded.contains(i)).collect()))
+    }
+
+    /// merge returns a new row with the columns of this row followed by the columns only found in `other`,
+    /// such as two reads of the same key with different column lists.
+    /// A column present in both rows must have the same value in each.
+    pub fn merge(&self, other: &Row) -> Result<Row, Error> {
+        let mut index = self.index.as_ref().clone();
+        let mut fields = self.fields.as_ref().clone();
+        let mut values = self.values.clone();
+        for c in other.columns() {
+            match self.column_index(c.name) {
+                Some(i) if &self.values[i] != c.value => return Err(Error::ConflictingColumn(c.name.to_string())),
+                Some(_) => {}
+                None => {
+                    index.insert(c.name.to_string(), fields.len());
+                    fields.push(c.field.clone());
+                    values.push(c.value.clone());
+                }
+            }
+        }
+        Ok(Row::new(Arc::new(index), Arc::new(fields), values))
+    }
+
+    fn project(&self, positions: Vec<usize>) -> Row {
+        let mut index = HashMap::with_capacity(positions.len());
+        let mut fields = Vec::with_capacity(positions.len());
+        let mut values = Vec::with_capacity(positions.len());
+        for (i, position) in positions.into_iter().enumerate() {
+            let field = &self.fields[position];
+            index.insert(field.name.clone(), i);
+            fields.push(field.clone());
+            values.push(self.values[position].clone());
+        }
+        Row::new(Arc::new(index), Arc::new(fields), values)
+    }
+}
+
+impl Row {
+    /// content_hash returns the same hash as the Hash impl, independent of the column order.
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        self.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// sorted_columns sorts the columns by name. The sort is stable, so columns that share a name
+    /// keep the order they were selected in.
+    fn sorted_columns(&self) -> Vec<ColumnValue<'_>> {
+        let mut columns: Vec<ColumnValue<'_>> = self.columns().collect();
+        columns.sort_by(|a, b| a.name.cmp(b.name));
+        columns
+    }
+}
+
+/// Rows are equal when they have the same columns, with the same types and values,
+/// regardless of the order the columns were selected in.
+/// Columns that share a name, such as the unnamed columns of `SELECT COUNT(*), MAX(x)`,
+/// are compared in the order they were selected in.
+impl PartialEq for Row {
+    fn eq(&self, other: &Self) -> bool {
+        self.fields.len() == other.fields.len()
+            && self
+                .sorted_columns()
+                .iter()
+                .zip(other.sorted_columns().iter())
+                .all(|(a, b)| a.name == b.name && a.field == b.field && a.value == b.value)
+    }
+}
+
+/// Spanner encodes NaN and infinite FLOAT64 values as strings, so values are always equal to themselves.
+impl Eq for Row {}
+
+impl Hash for Row {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for c in self.sorted_columns() {
+            c.name.hash(state);
+            hash_value(c.value, state);
+        }
+    }
+}
+
+fn hash_value<H: Hasher>(value: &Value, state: &mut H) {
+    match &value.kind {
+        None | Some(Kind::NullValue(_)) => 0_u8.hash(state),
+        Some(Kind::BoolValue(v)) => (1_u8, v).hash(state),
+        // 0.0 and -0.0 are equal, so they must hash the same
+        Some(Kind::NumberValue(v)) => (2_u8, if *v == 0.0 { 0 } else { v.to_bits() }).hash(state),
+        Some(Kind::StringValue(v)) => (3_u8, v).hash(state),
+        Some(Kind::ListValue(v)) => {
+            (4_u8, v.values.len()).hash(state);
+            v.values.iter().for_each(|v| hash_value(v, state));
+        }
+        Some(Kind::StructValue(v)) => {
+            (5_u8, v.fields.len()).hash(state);
+            v.fields.iter().for_each(|(k, v)| {
+                k.hash(state);
+                hash_value(v, state);
+            });
+        }
+    }
+}
+
+/// ColumnValue is a single column of a Row, borrowed from it.
+pub struct ColumnValue<'a> {
+    pub name: &'a str,
+    pub field: &'a Field,
+    pub value: &'a Value,
+}
+
+impl ColumnValue<'_> {
+    pub fn get<T>(&self) -> Result<T, Error>
+    where
+        T: TryFromValue,
+    {
+        T::try_from(self.value, self.field)
+    }
+}
+
+/// TypedColumn names a column together with the type it is read as,
+/// so that the type is declared once instead of at every `column_by_name` call.
+/// ```
+/// use google_cloud_spanner::row::{Error, Row, TypedColumn};
+///
+/// const LEVEL: TypedColumn<i64> = TypedColumn::new("Level");
+///
+/// fn level(row: &Row) -> Result<i64, Error> {
+///     LEVEL.get(row)
+/// }
+/// ```
+pub struct TypedColumn<T> {
+    name: &'static str,
+    _marker: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedColumn<T> {
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+}
+
+impl<T: TryFromValue> TypedColumn<T> {
+    pub fn get(&self, row: &Row) -> Result<T, Error> {
+        row.column_by_name(self.name)
+    }
+}
+
+impl<T> Clone for TypedColumn<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for TypedColumn<T> {}
+
+/// ColumnDiff is the difference of a single column between two rows.
+/// `before` or `after` is None when the column is missing from that row.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ColumnDiff {
+    pub column_name: String,
+    /// column_type is the type of the column in the row it was read from, if the metadata has one.
+    pub column_type: Option<Type>,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub changed: bool,
+}
+
+impl ColumnDiff {
+    /// display_patch renders the column as `name: before -> after`, or `name: value` when unchanged.
+    pub fn display_patch(&self) -> String {
+        let column_type = self.column_type.as_ref();
+        let render = |v: &Option<Value>| {
+            v.as_ref()
+                .map_or("(missing)".to_string(), |v| display_typed_value(v, column_type))
+        };
+        if self.changed {
+            format!("{}: {} -> {}", self.column_name, render(&self.before), render(&self.after))
+        } else {
+            format!("{}: {}", self.column_name, render(&self.before))
+        }
+    }
+}
+
+/// diff_rows compares two rows column by column, such as the old and new values of a change stream record.
+/// Columns of `before` come first in their order, followed by the columns found only in `after`.
+pub fn diff_rows(before: &Row, after: &Row) -> Vec<ColumnDiff> {
+    let mut diffs: Vec<ColumnDiff> = before
+        .columns()
+        .map(|c| {
+            let after = after.column_index(c.name).map(|i| after.values[i].clone());
+            ColumnDiff {
+                column_name: c.name.to_string(),
+                column_type: c.field.r#type.clone(),
+                changed: after.as_ref() != Some(c.value),
+                before: Some(c.value.clone()),
+                after,
+            }
+        })
+        .collect();
+    diffs.extend(
+        after
+            .columns()
+            .filter(|c| before.column_index(c.name).is_none())
+            .map(|c| ColumnDiff {
+                column_name: c.name.to_string(),
+                column_type: c.field.r#type.clone(),
+                before: None,
+                after: Some(c.value.clone()),
+                changed: true,
+            }),
+    );
+    diffs
+}
+
+pub(crate) fn display_value(value: &Value) -> String {
+    display_typed_value(value, None)
+}
+
+/// display_typed_value prints INT64 values, which are encoded as strings, without quotes when the type is known.
+fn display_typed_value(value: &Value, r#type: Option<&Type>) -> String {
+    match &value.kind {
+        None | Some(Kind::NullValue(_)) => "NULL".to_string(),
+        Some(Kind::BoolValue(v)) => v.to_string(),
+        Some(Kind::NumberValue(v)) => v.to_string(),
+        Some(Kind::StringValue(v)) if matches!(r#type, Some(t) if t.code == TypeCode::Int64 as i32) => v.clone(),
+        Some(Kind::StringValue(v)) => format!("{v:?}"),
+        Some(Kind::ListValue(v)) => {
+            let element_type = r#type.and_then(|t| t.array_element_type.as_deref());
+            let values: Vec<String> = v.values.iter().map(|v| display_typed_value(v, element_type)).collect();
+            format!("[{}]", values.join(", "))
+        }
+        Some(Kind::StructValue(v)) => {
+            let fields: Vec<String> = v
+                .fields
+                .iter()
+                .map(|(k, v)| format!("{k}: {}", display_value(v)))
+                .collect();
+            format!("{{{}}}", fields.join(", "))
+        }
+    }
 }
 
 //don't use TryFrom trait to avoid the conflict
@@ -123,7 +429,10 @@ impl<'a> Struct<'a> {
             Some(values) => column(values, &self.metadata.fields, column_index),
             None => match self.struct_values {
                 Some(values) => {
-                    let field = &self.metadata.fields[column_index];
+                    let field = match self.metadata.fields.get(column_index) {
+                        Some(field) => field,
+                        None => return Err(Error::InvalidColumnIndex(column_index, self.metadata.fields.len())),
+                    };
                     let name = &field.name;
                     match values.get(name) {
                         Some(value) => T::try_from(value, field),
@@ -181,6 +490,26 @@ impl TryFromValue for OffsetDateTime {
     }
 }
 
+#[cfg(feature = "chrono")]
+impl TryFromValue for chrono::DateTime<chrono::FixedOffset> {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::StringValue(s) => {
+                chrono::DateTime::parse_from_rfc3339(s).map_err(|e| Error::ChronoParseError(field.name.to_string(), e))
+            }
+            v => kind_to_error(v, field),
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFromValue for chrono::DateTime<chrono::Utc> {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        let value: chrono::DateTime<chrono::FixedOffset> = TryFromValue::try_from(item, field)?;
+        Ok(value.with_timezone(&chrono::Utc))
+    }
+}
+
 impl TryFromValue for ::prost_types::Timestamp {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
@@ -231,6 +560,48 @@ impl TryFromValue for BigDecimal {
     }
 }
 
+impl TryFromValue for std::net::IpAddr {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::StringValue(s) => s.parse().map_err(|e| Error::AddrParseError(field.name.to_string(), e)),
+            v => kind_to_error(v, field),
+        }
+    }
+}
+
+impl TryFromValue for std::net::SocketAddr {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::StringValue(s) => s.parse().map_err(|e| Error::AddrParseError(field.name.to_string(), e)),
+            v => kind_to_error(v, field),
+        }
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl TryFromValue for uuid::Uuid {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::StringValue(s) => {
+                uuid::Uuid::parse_str(s).map_err(|e| Error::UuidParseError(field.name.to_string(), e))
+            }
+            v => kind_to_error(v, field),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl TryFromValue for serde_json::Value {
+    fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
+        match as_ref(item, field)? {
+            Kind::StringValue(s) => {
+                serde_json::from_str(s).map_err(|e| Error::JsonParseError(field.name.to_string(), e))
+            }
+            v => kind_to_error(v, field),
+        }
+    }
+}
+
 impl TryFromValue for String {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
         match as_ref(item, field)? {
@@ -245,21 +616,18 @@ where
     T: TryFromStruct,
 {
     fn try_from(item: &Value, field: &Field) -> Result<Self, Error> {
-        let maybe_array = match field.r#type.as_ref() {
+        let tp = match field.r#type.as_ref() {
             None => return Err(Error::StructParseError(field.name.to_string(), "field type must not be none")),
-            Some(tp) => tp.array_element_type.as_ref(),
+            Some(tp) => tp,
         };
-        let maybe_struct_type = match maybe_array {
-            None => return Err(Error::StructParseError(field.name.to_string(), "array must not be none")),
-            Some(tp) => tp.struct_type.as_ref(),
+        // STRUCT nested in another STRUCT carries its own struct type,
+        // while the elements of ARRAY<STRUCT> share the type of the array.
+        let maybe_struct_type = match tp.struct_type.as_ref() {
+            Some(struct_type) => Some(struct_type),
+            None => tp.array_element_type.as_ref().and_then(|tp| tp.struct_type.as_ref()),
         };
         let struct_type = match maybe_struct_type {
-            None => {
-                return Err(Error::StructParseError(
-                    field.name.to_string(),
-                    "struct type in array must not be none ",
-                ))
-            }
+            None => return Err(Error::StructParseError(field.name.to_string(), "struct type must not be none")),
             Some(struct_type) => struct_type,
         };
 
@@ -335,13 +703,15 @@ mod tests {
     use std::str::FromStr;
     use std::sync::Arc;
 
-    use prost_types::{Timestamp, Value};
+    use prost_types::value::Kind;
+    use prost_types::{ListValue, Timestamp, Value};
     use time::OffsetDateTime;
 
     use google_cloud_googleapis::spanner::v1::struct_type::Field;
+    use google_cloud_googleapis::spanner::v1::Type;
 
     use crate::bigdecimal::{BigDecimal, FromPrimitive, ToPrimitive, Zero};
-    use crate::row::{Error, Row, Struct as RowStruct, TryFromStruct};
+    use crate::row::{diff_rows, Error, Row, Struct as RowStruct, TryFromStruct, TryFromValue, TypedColumn};
     use crate::statement::{Kinds, ToKind, ToStruct, Types};
     use crate::value::CommitTimestamp;
 
@@ -491,4 +861,395 @@ mod tests {
         );
         assert_eq!(format!("{}", struct_data[1].prost_timestamp), "2027-02-19T07:23:59Z");
     }
+
+    struct Inner {
+        pub value: i64,
+    }
+
+    impl TryFromStruct for Inner {
+        fn try_from_struct(s: RowStruct<'_>) -> Result<Self, Error> {
+            Ok(Inner {
+                value: s.column_by_name("value")?,
+            })
+        }
+    }
+
+    impl ToStruct for Inner {
+        fn to_kinds(&self) -> Kinds {
+            vec![("value", self.value.to_kind())]
+        }
+
+        fn get_types() -> Types {
+            vec![("value", i64::get_type())]
+        }
+    }
+
+    struct Outer {
+        pub name: String,
+        pub inner: Inner,
+    }
+
+    impl TryFromStruct for Outer {
+        fn try_from_struct(s: RowStruct<'_>) -> Result<Self, Error> {
+            Ok(Outer {
+                name: s.column_by_name("name")?,
+                inner: s.column_by_name("inner")?,
+            })
+        }
+    }
+
+    impl ToStruct for Outer {
+        fn to_kinds(&self) -> Kinds {
+            vec![("name", self.name.to_kind()), ("inner", self.inner.to_kind())]
+        }
+
+        fn get_types() -> Types {
+            vec![("name", String::get_type()), ("inner", Inner::get_type())]
+        }
+    }
+
+    #[test]
+    fn test_try_from_nested_struct() {
+        let mut index = HashMap::new();
+        index.insert("array".to_string(), 0);
+
+        // SELECT ARRAY(SELECT AS STRUCT "aaa" AS name, STRUCT(1 AS value) AS inner) AS array
+        let list = |values: Vec<Kind>| {
+            Kind::ListValue(ListValue {
+                values: values.into_iter().map(|kind| Value { kind: Some(kind) }).collect(),
+            })
+        };
+        let row = Row {
+            index: Arc::new(index),
+            fields: Arc::new(vec![Field {
+                name: "array".to_string(),
+                r#type: Some(Vec::<Outer>::get_type()),
+            }]),
+            values: vec![Value {
+                kind: Some(list(vec![list(vec!["aaa".to_kind(), list(vec![1_i64.to_kind()])])])),
+            }],
+        };
+
+        let outer = row.column_by_name::<Vec<Outer>>("array").unwrap();
+        assert_eq!(outer.len(), 1);
+        assert_eq!(outer[0].name, "aaa");
+        assert_eq!(outer[0].inner.value, 1);
+    }
+
+    fn assert_round_trip<T>(v: T)
+    where
+        T: ToKind + TryFromValue + PartialEq + std::fmt::Debug,
+    {
+        let field = Field {
+            name: "array".to_string(),
+            r#type: Some(T::get_type()),
+        };
+        let value = Value {
+            kind: Some(v.to_kind()),
+        };
+        assert_eq!(T::try_from(&value, &field).unwrap(), v);
+    }
+
+    #[test]
+    fn test_array_round_trip() {
+        let date = time::macros::date!(2024 - 02 - 29);
+        let ts = time::macros::datetime!(2024-02-29 12:34:56.789012345 UTC);
+        for len in [0, 1, 3] {
+            assert_round_trip((0..len).map(|i| date.add(time::Duration::days(i))).collect::<Vec<_>>());
+            assert_round_trip((0..len).map(|i| ts.add(time::Duration::seconds(i))).collect::<Vec<_>>());
+            assert_round_trip((0..len).map(|i| i % 2 == 0).collect::<Vec<_>>());
+            assert_round_trip((0..len).map(|i| format!("value{i}")).collect::<Vec<_>>());
+            assert_round_trip((0..len).map(|i| (i != 1).then_some(date)).collect::<Vec<_>>());
+            assert_round_trip((0..len).map(|i| (i != 1).then_some(ts)).collect::<Vec<_>>());
+            assert_round_trip((0..len).map(|i| (i != 1).then_some(i == 0)).collect::<Vec<_>>());
+            assert_round_trip((0..len).map(|i| (i != 1).then(|| i.to_string())).collect::<Vec<_>>());
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono_date_time() {
+        use chrono::{DateTime, FixedOffset, TimeZone, Utc};
+
+        let utc = Utc.with_ymd_and_hms(2024, 2, 29, 12, 34, 56).unwrap() + chrono::Duration::nanoseconds(789012345);
+        let offset = utc.with_timezone(&FixedOffset::east_opt(9 * 3600).unwrap());
+        assert_eq!(utc.to_kind(), Kind::StringValue("2024-02-29T12:34:56.789012345Z".to_string()));
+        assert_eq!(
+            offset.to_kind(),
+            Kind::StringValue("2024-02-29T21:34:56.789012345+09:00".to_string())
+        );
+        assert_round_trip(utc);
+        assert_round_trip(offset);
+        assert_round_trip(vec![Some(utc), None]);
+
+        // Cloud Spanner always returns timestamps in UTC.
+        let field = Field {
+            name: "ts".to_string(),
+            r#type: Some(DateTime::<Utc>::get_type()),
+        };
+        let value = Value {
+            kind: Some(time::macros::datetime!(2024-02-29 12:34:56.789012345 UTC).to_kind()),
+        };
+        assert_eq!(<DateTime<Utc> as TryFromValue>::try_from(&value, &field).unwrap(), utc);
+        let value = Value {
+            kind: Some("2024-02-29".to_kind()),
+        };
+        match <DateTime<Utc> as TryFromValue>::try_from(&value, &field) {
+            Err(Error::ChronoParseError(name, _)) => assert_eq!(name, "ts"),
+            _ => panic!("date must not be parsed as timestamp"),
+        }
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_json_round_trip() {
+        use serde_json::json;
+
+        assert_round_trip(json!({"name": "spanner", "nested": {"list": [1, 2.5, "three"], "empty": {}}}));
+        assert_round_trip(json!([{"id": 1}, [true, false], null]));
+        assert_round_trip(json!(null));
+        assert_round_trip(vec![json!({"a": 1}), json!([])]);
+        assert_round_trip(Some(json!("text")));
+        assert_round_trip::<Option<serde_json::Value>>(None);
+
+        let field = Field {
+            name: "data".to_string(),
+            r#type: Some(serde_json::Value::get_type()),
+        };
+        let value = Value {
+            kind: Some("{invalid".to_kind()),
+        };
+        match <serde_json::Value as TryFromValue>::try_from(&value, &field) {
+            Err(Error::JsonParseError(name, _)) => assert_eq!(name, "data"),
+            _ => panic!("invalid json must fail"),
+        }
+    }
+
+    #[test]
+    fn test_network_address() {
+        use std::net::{IpAddr, SocketAddr};
+
+        let field = |name: &str| Field {
+            name: name.to_string(),
+            r#type: Some(IpAddr::get_type()),
+        };
+        let value = |kind: Kind| Value { kind: Some(kind) };
+        let v4: IpAddr = "192.168.0.1".parse().unwrap();
+        let v6: IpAddr = "2001:db8::1".parse().unwrap();
+        let socket: SocketAddr = "[2001:db8::1]:8080".parse().unwrap();
+        let mut index = HashMap::new();
+        for (i, name) in ["v4", "v6", "socket", "invalid"].iter().enumerate() {
+            index.insert(name.to_string(), i);
+        }
+        let row = Row::new(
+            Arc::new(index),
+            Arc::new(vec![field("v4"), field("v6"), field("socket"), field("invalid")]),
+            vec![
+                value(v4.to_kind()),
+                value(v6.to_kind()),
+                value(socket.to_kind()),
+                value("localhost".to_kind()),
+            ],
+        );
+        assert_eq!(row.column_by_name::<IpAddr>("v4").unwrap(), v4);
+        assert_eq!(row.column_by_name::<IpAddr>("v6").unwrap(), v6);
+        assert_eq!(row.column_by_name::<SocketAddr>("socket").unwrap(), socket);
+        assert_eq!(row.column_by_name::<String>("socket").unwrap(), "[2001:db8::1]:8080");
+        match row.column_by_name::<IpAddr>("invalid") {
+            Err(Error::AddrParseError(name, _)) => assert_eq!(name, "invalid"),
+            _ => panic!("must be parse error"),
+        }
+        assert!(row.column_by_name::<SocketAddr>("v4").is_err());
+    }
+
+    fn column<T: ToKind>(value: T) -> (Kind, Type) {
+        (value.to_kind(), T::get_type())
+    }
+
+    fn row(columns: &[(&str, (Kind, Type))]) -> Row {
+        let mut index = HashMap::new();
+        let mut fields = vec![];
+        let mut values = vec![];
+        for (i, (name, (kind, r#type))) in columns.iter().enumerate() {
+            index.insert(name.to_string(), i);
+            fields.push(Field {
+                name: name.to_string(),
+                r#type: Some(r#type.clone()),
+            });
+            values.push(Value {
+                kind: Some(kind.clone()),
+            });
+        }
+        Row::new(Arc::new(index), Arc::new(fields), values)
+    }
+
+    #[test]
+    fn test_row_eq_and_hash() {
+        let a = row(&[("name", column("aaa")), ("level", column(1_i64)), ("rate", column(0.0))]);
+        let b = row(&[
+            ("rate", column(-0.0)),
+            ("level", column(1_i64)),
+            ("name", column("aaa")),
+        ]);
+        let c = row(&[("name", column("aaa")), ("level", column(2_i64)), ("rate", column(0.0))]);
+        let d = row(&[("name", column("aaa")), ("level", column(1_i64))]);
+        assert!(a == b);
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert!(a != c);
+        assert_ne!(a.content_hash(), c.content_hash());
+        assert!(a != d);
+        assert!(d != a);
+
+        let set: std::collections::HashSet<Row> = [a, b, c, d].into_iter().collect();
+        assert_eq!(set.len(), 3);
+
+        // SELECT COUNT(*), MAX(x) returns two unnamed columns
+        let a = row(&[("", column(7_i64)), ("", column(7_i64))]);
+        let b = row(&[("", column(5_i64)), ("", column(7_i64))]);
+        let c = row(&[("", column(7_i64)), ("", column(5_i64))]);
+        assert!(a != b);
+        assert!(b != a);
+        assert!(b != c);
+        assert!(b == b.clone());
+        assert_eq!(b.content_hash(), b.clone().content_hash());
+        let set: std::collections::HashSet<Row> = [a.clone(), a, b.clone(), b, c].into_iter().collect();
+        assert_eq!(set.len(), 3);
+    }
+
+    #[test]
+    fn test_column_index_out_of_bounds() {
+        let field = Field {
+            name: "struct".to_string(),
+            r#type: None,
+        };
+        let metadata = google_cloud_googleapis::spanner::v1::StructType {
+            fields: vec![Field {
+                name: "value".to_string(),
+                r#type: Some(i64::get_type()),
+            }],
+        };
+        let value = Value {
+            kind: Some(Kind::StructValue(prost_types::Struct {
+                fields: [(
+                    "value".to_string(),
+                    Value {
+                        kind: Some(1_i64.to_kind()),
+                    },
+                )]
+                .into(),
+            })),
+        };
+        let s = RowStruct::new(&metadata, &value, &field).unwrap();
+        assert_eq!(s.column::<i64>(0).unwrap(), 1);
+        match s.column::<i64>(1) {
+            Err(Error::InvalidColumnIndex(1, 1)) => {}
+            _ => panic!("must be invalid column index"),
+        }
+
+        let row = Row::new(Arc::new(HashMap::new()), Arc::new(vec![field]), vec![value]);
+        match row.column::<i64>(1) {
+            Err(Error::InvalidColumnIndex(1, 1)) => {}
+            _ => panic!("must be invalid column index"),
+        }
+    }
+
+    #[test]
+    fn test_columns() {
+        let mut index = HashMap::new();
+        index.insert("name".to_string(), 0);
+        index.insert("level".to_string(), 1);
+        let row = Row {
+            index: Arc::new(index),
+            fields: Arc::new(vec![
+                Field {
+                    name: "name".to_string(),
+                    r#type: Some(String::get_type()),
+                },
+                Field {
+                    name: "level".to_string(),
+                    r#type: Some(i64::get_type()),
+                },
+            ]),
+            values: vec![
+                Value {
+                    kind: Some("aaa".to_kind()),
+                },
+                Value {
+                    kind: Some(10_i64.to_kind()),
+                },
+            ],
+        };
+
+        assert_eq!(row.column_names(), vec!["name", "level"]);
+        assert_eq!(row.columns_metadata()[1].r#type, Some(i64::get_type()));
+        assert_eq!(row.column_index("level"), Some(1));
+        assert_eq!(row.column_index("unknown"), None);
+
+        let columns: Vec<_> = row.columns().collect();
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0].name, "name");
+        assert_eq!(columns[0].get::<String>().unwrap(), "aaa");
+        assert_eq!(columns[1].name, "level");
+        assert_eq!(columns[1].get::<i64>().unwrap(), 10);
+
+        const LEVEL: TypedColumn<i64> = TypedColumn::new("level");
+        assert_eq!(LEVEL.get(&row).unwrap(), 10);
+        assert!(TypedColumn::<bool>::new("level").get(&row).is_err());
+
+        let selected = row.select(&["level"]).unwrap();
+        assert_eq!(selected.column_names(), vec!["level"]);
+        assert_eq!(selected.column_by_name::<i64>("level").unwrap(), 10);
+        assert!(row.select(&["level", "unknown"]).is_err());
+
+        let excluded = row.exclude(&["level"]).unwrap();
+        assert_eq!(excluded.column_names(), vec!["name"]);
+        assert_eq!(excluded.column::<String>(0).unwrap(), "aaa");
+        assert!(row.exclude(&["unknown"]).is_err());
+
+        let merged = row.exclude(&["level"]).unwrap().merge(&selected).unwrap();
+        assert_eq!(merged.column_names(), vec!["name", "level"]);
+        assert_eq!(merged.column_by_name::<i64>("level").unwrap(), 10);
+        assert_eq!(row.merge(&selected).unwrap().column_names(), vec!["name", "level"]);
+        let mut conflicting = selected.clone();
+        conflicting.values[0] = Value {
+            kind: Some(11_i64.to_kind()),
+        };
+        match row.merge(&conflicting) {
+            Err(Error::ConflictingColumn(name)) => assert_eq!(name, "level"),
+            _ => panic!("must be conflicting"),
+        }
+    }
+
+    #[test]
+    fn test_diff_rows() {
+        let before = row(&[
+            ("name", column("aaa")),
+            ("level", column(1_i64)),
+            ("old", column(true)),
+            ("ids", column(vec![1_i64, 2])),
+        ]);
+        let after = row(&[
+            ("level", column(2_i64)),
+            ("name", column("aaa")),
+            ("new", column(None::<i64>)),
+            ("ids", column(vec![1_i64, 3])),
+        ]);
+
+        let diffs = diff_rows(&before, &after);
+        let names: Vec<&str> = diffs.iter().map(|d| d.column_name.as_str()).collect();
+        assert_eq!(names, vec!["name", "level", "old", "ids", "new"]);
+        let changed: Vec<bool> = diffs.iter().map(|d| d.changed).collect();
+        assert_eq!(changed, vec![false, true, true, true, true]);
+        let patches: Vec<String> = diffs.iter().map(|d| d.display_patch()).collect();
+        assert_eq!(
+            patches,
+            vec![
+                "name: \"aaa\"",
+                "level: 1 -> 2",
+                "old: true -> (missing)",
+                "ids: [1, 2] -> [1, 3]",
+                "new: (missing) -> NULL"
+            ]
+        );
+    }
 }
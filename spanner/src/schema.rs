@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+
+use crate::client::{Client, Error};
+use crate::row::Row;
+use crate::statement::Statement;
+use crate::transaction_ro::ReadOnlyTransaction;
+
+const TABLES: &str = "SELECT TABLE_NAME, PARENT_TABLE_NAME FROM INFORMATION_SCHEMA.TABLES WHERE TABLE_SCHEMA = ''";
+
+const COLUMNS: &str = "SELECT TABLE_NAME, COLUMN_NAME, ORDINAL_POSITION, SPANNER_TYPE, IS_NULLABLE
+    FROM INFORMATION_SCHEMA.COLUMNS WHERE TABLE_SCHEMA = '' ORDER BY TABLE_NAME, ORDINAL_POSITION";
+
+const INDEXES: &str = "SELECT TABLE_NAME, INDEX_NAME, IS_UNIQUE
+    FROM INFORMATION_SCHEMA.INDEXES WHERE TABLE_SCHEMA = '' AND INDEX_TYPE = 'INDEX'";
+
+const INDEX_COLUMNS: &str = "SELECT TABLE_NAME, INDEX_NAME, COLUMN_NAME, ORDINAL_POSITION
+    FROM INFORMATION_SCHEMA.INDEX_COLUMNS WHERE TABLE_SCHEMA = '' AND ORDINAL_POSITION IS NOT NULL
+    ORDER BY TABLE_NAME, INDEX_NAME, ORDINAL_POSITION";
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct ColumnSchema {
+    pub name: String,
+    /// spanner_type is the type as written in the DDL, e.g. `STRING(36)` or `ARRAY<INT64>`.
+    pub spanner_type: String,
+    pub nullable: bool,
+    pub ordinal_position: i64,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct IndexSchema {
+    pub name: String,
+    pub unique: bool,
+    /// key_columns are the key columns of the index in key order, excluding the stored columns.
+    pub key_columns: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TableSchema {
+    pub name: String,
+    /// parent_table is the table this table is interleaved in.
+    pub parent_table: Option<String>,
+    pub columns: Vec<ColumnSchema>,
+    /// primary_key are the primary key columns in key order.
+    pub primary_key: Vec<String>,
+    pub indexes: Vec<IndexSchema>,
+}
+
+/// Schema is a snapshot of the tables of the database, read from INFORMATION_SCHEMA.
+/// It is not updated by DDL changes until `refresh` is called.
+/// ```
+/// use google_cloud_spanner::client::{Client, Error};
+/// use google_cloud_spanner::schema::Schema;
+///
+/// async fn run(client: &Client) -> Result<(), Error> {
+///     let schema = Schema::from_information_schema(client).await?;
+///     if let Some(table) = schema.tables.get("User") {
+///         println!("primary key: {:?}", table.primary_key);
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct Schema {
+    pub tables: HashMap<String, TableSchema>,
+}
+
+impl Schema {
+    pub async fn from_information_schema(client: &Client) -> Result<Schema, Error> {
+        let mut tx = client.read_only_transaction().await?;
+        let mut tables: HashMap<String, TableSchema> = HashMap::new();
+        for row in query(&mut tx, TABLES).await? {
+            let name: String = row.column(0)?;
+            tables.insert(
+                name.clone(),
+                TableSchema {
+                    name,
+                    parent_table: row.column(1)?,
+                    columns: vec![],
+                    primary_key: vec![],
+                    indexes: vec![],
+                },
+            );
+        }
+        for row in query(&mut tx, COLUMNS).await? {
+            if let Some(table) = tables.get_mut(&row.column::<String>(0)?) {
+                table.columns.push(ColumnSchema {
+                    name: row.column(1)?,
+                    ordinal_position: row.column(2)?,
+                    spanner_type: row.column::<Option<String>>(3)?.unwrap_or_default(),
+                    nullable: row.column::<String>(4)? == "YES",
+                });
+            }
+        }
+        for row in query(&mut tx, INDEXES).await? {
+            if let Some(table) = tables.get_mut(&row.column::<String>(0)?) {
+                table.indexes.push(IndexSchema {
+                    name: row.column(1)?,
+                    unique: row.column(2)?,
+                    key_columns: vec![],
+                });
+            }
+        }
+        for row in query(&mut tx, INDEX_COLUMNS).await? {
+            let table = match tables.get_mut(&row.column::<String>(0)?) {
+                Some(table) => table,
+                None => continue,
+            };
+            let index_name: String = row.column(1)?;
+            let column_name: String = row.column(2)?;
+            if index_name == "PRIMARY_KEY" {
+                table.primary_key.push(column_name);
+            } else if let Some(index) = table.indexes.iter_mut().find(|i| i.name == index_name) {
+                index.key_columns.push(column_name);
+            }
+        }
+        Ok(Schema { tables })
+    }
+
+    /// refresh reads the schema again, e.g. after running DDL.
+    pub async fn refresh(&mut self, client: &Client) -> Result<(), Error> {
+        *self = Schema::from_information_schema(client).await?;
+        Ok(())
+    }
+}
+
+async fn query(tx: &mut ReadOnlyTransaction, sql: &str) -> Result<Vec<Row>, Error> {
+    let mut iter = tx.query(Statement::new(sql)).await?;
+    let mut rows = vec![];
+    while let Some(row) = iter.next().await? {
+        rows.push(row);
+    }
+    Ok(rows)
+}
@@ -0,0 +1,232 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{ready, Context, Poll};
+
+use futures_util::Sink;
+use tokio::sync::{mpsc, oneshot};
+
+use google_cloud_gax::grpc::Status;
+use google_cloud_googleapis::spanner::v1::Mutation;
+
+use crate::client::{Client, Error, ReadWriteTransactionOption};
+use crate::value::Timestamp;
+
+type Commit = Pin<Box<dyn Future<Output = Result<Option<Timestamp>, Error>> + Send>>;
+
+/// CloudSpannerSink writes a stream of mutations to the database.
+///
+/// Mutations are buffered and committed in a read-write transaction each time
+/// the buffer reaches `buffer_size`, or when the sink is flushed or closed.
+/// Each commit is atomic on its own, but the stream as a whole is not.
+/// ```
+/// use futures_util::SinkExt;
+/// use google_cloud_spanner::client::{Client, Error};
+/// use google_cloud_spanner::mutation::insert;
+/// use google_cloud_spanner::sink::CloudSpannerSink;
+///
+/// async fn run(client: Client) -> Result<(), Error> {
+///     let mut sink = CloudSpannerSink::new(client, 100);
+///     for i in 0..1000_i64 {
+///         sink.feed(insert("Guild", &["GuildId"], &[&i])).await?;
+///     }
+///     sink.close().await
+/// }
+/// ```
+pub struct CloudSpannerSink {
+    client: Client,
+    options: ReadWriteTransactionOption,
+    buffer_size: usize,
+    buffer: Vec<Mutation>,
+    commit: Option<Commit>,
+}
+
+impl CloudSpannerSink {
+    pub fn new(client: Client, buffer_size: usize) -> Self {
+        Self::new_with_option(client, buffer_size, ReadWriteTransactionOption::default())
+    }
+
+    pub fn new_with_option(client: Client, buffer_size: usize, options: ReadWriteTransactionOption) -> Self {
+        Self {
+            client,
+            options,
+            buffer_size: buffer_size.max(1),
+            buffer: Vec::with_capacity(buffer_size),
+            commit: None,
+        }
+    }
+
+    fn poll_commit(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Error>> {
+        loop {
+            if let Some(commit) = self.commit.as_mut() {
+                let result = ready!(commit.as_mut().poll(cx));
+                self.commit = None;
+                result?;
+            }
+            if self.buffer.is_empty() {
+                return Poll::Ready(Ok(()));
+            }
+            let ms = std::mem::replace(&mut self.buffer, Vec::with_capacity(self.buffer_size));
+            let client = self.client.clone();
+            let options = self.options.clone();
+            self.commit = Some(Box::pin(async move { client.apply_with_option(ms, options).await }));
+        }
+    }
+}
+
+impl Sink<Mutation> for CloudSpannerSink {
+    type Error = Error;
+
+    fn poll_ready(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let this = self.get_mut();
+        if this.buffer.len() >= this.buffer_size {
+            this.poll_commit(cx)
+        } else {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Mutation) -> Result<(), Self::Error> {
+        self.get_mut().buffer.push(item);
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_commit(cx)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().poll_commit(cx)
+    }
+}
+
+enum Message {
+    Mutation(Mutation),
+    Flush(oneshot::Sender<Result<Option<Timestamp>, Error>>),
+}
+
+/// AsyncMutationBuffer lets many tasks feed mutations to a single committer.
+///
+/// The channel between the senders and the receiver holds at most `max_size`
+/// messages, so `MutationSender::send` waits while the receiver is busy committing.
+/// The receiver commits its buffer in a read-write transaction each time it
+/// reaches `max_size` mutations, on every `MutationSender::flush`, and once all
+/// the senders have been dropped.
+/// ```
+/// use google_cloud_spanner::client::{Client, Error};
+/// use google_cloud_spanner::mutation::insert;
+/// use google_cloud_spanner::sink::AsyncMutationBuffer;
+///
+/// async fn run(client: Client) -> Result<(), Error> {
+///     let (sender, receiver) = AsyncMutationBuffer::new(100);
+///     let committer = tokio::spawn(receiver.run(client));
+///     for i in 0..10_i64 {
+///         let sender = sender.clone();
+///         let mutation = insert("Guild", &["GuildId"], &[&i]);
+///         tokio::spawn(async move { sender.send(mutation).await });
+///     }
+///     sender.flush().await?;
+///     drop(sender);
+///     committer.await.unwrap()?;
+///     Ok(())
+/// }
+/// ```
+pub struct AsyncMutationBuffer;
+
+impl AsyncMutationBuffer {
+    #[allow(clippy::new_ret_no_self)]
+    pub fn new(max_size: usize) -> (MutationSender, MutationReceiver) {
+        let max_size = max_size.max(1);
+        let (tx, rx) = mpsc::channel(max_size);
+        (MutationSender { tx }, MutationReceiver { rx, max_size })
+    }
+}
+
+fn receiver_closed() -> Error {
+    Error::GRPC(Status::cancelled("mutation receiver is closed"))
+}
+
+#[derive(Clone)]
+pub struct MutationSender {
+    tx: mpsc::Sender<Message>,
+}
+
+impl MutationSender {
+    /// send waits until the receiver has room for the mutation.
+    pub async fn send(&self, mutation: Mutation) -> Result<(), Error> {
+        self.tx
+            .send(Message::Mutation(mutation))
+            .await
+            .map_err(|_| receiver_closed())
+    }
+
+    /// flush asks the receiver to commit everything sent before it and waits for the result.
+    /// If an automatic commit failed since the previous flush, its error is returned instead.
+    pub async fn flush(&self) -> Result<Option<Timestamp>, Error> {
+        let (tx, rx) = oneshot::channel();
+        self.tx.send(Message::Flush(tx)).await.map_err(|_| receiver_closed())?;
+        rx.await.map_err(|_| receiver_closed())?
+    }
+}
+
+pub struct MutationReceiver {
+    rx: mpsc::Receiver<Message>,
+    max_size: usize,
+}
+
+impl MutationReceiver {
+    /// run commits the received mutations until all the senders have been dropped.
+    /// It returns the outcome of the commits made since the last flush, if any.
+    pub async fn run(self, client: Client) -> Result<Option<Timestamp>, Error> {
+        self.run_with_option(client, ReadWriteTransactionOption::default())
+            .await
+    }
+
+    pub async fn run_with_option(
+        mut self,
+        client: Client,
+        options: ReadWriteTransactionOption,
+    ) -> Result<Option<Timestamp>, Error> {
+        let mut buffer = Vec::with_capacity(self.max_size);
+        let mut pending = Ok(None);
+        while let Some(message) = self.rx.recv().await {
+            match message {
+                Message::Mutation(mutation) => {
+                    buffer.push(mutation);
+                    if buffer.len() >= self.max_size {
+                        let result = commit(&client, &mut buffer, &options).await;
+                        merge(&mut pending, result);
+                    }
+                }
+                Message::Flush(reply) => {
+                    let result = commit(&client, &mut buffer, &options).await;
+                    let mut flushed = std::mem::replace(&mut pending, Ok(None));
+                    merge(&mut flushed, result);
+                    let _ = reply.send(flushed);
+                }
+            }
+        }
+        let result = commit(&client, &mut buffer, &options).await;
+        merge(&mut pending, result);
+        pending
+    }
+}
+
+async fn commit(
+    client: &Client,
+    buffer: &mut Vec<Mutation>,
+    options: &ReadWriteTransactionOption,
+) -> Result<Option<Timestamp>, Error> {
+    if buffer.is_empty() {
+        return Ok(None);
+    }
+    let ms = std::mem::take(buffer);
+    client.apply_with_option(ms, options.clone()).await
+}
+
+/// merge keeps the first error, otherwise the latest commit timestamp.
+fn merge(pending: &mut Result<Option<Timestamp>, Error>, result: Result<Option<Timestamp>, Error>) {
+    match (&pending, result) {
+        (Err(_), _) | (Ok(_), Ok(None)) => {}
+        (Ok(_), result) => *pending = result,
+    }
+}
@@ -3,7 +3,7 @@ use std::collections::{BTreeMap, HashMap};
 use base64::prelude::*;
 use prost_types::value::Kind;
 use prost_types::value::Kind::StringValue;
-use prost_types::{value, ListValue, Struct, Value};
+use prost_types::{value, ListValue, Value};
 use time::format_description::well_known::Rfc3339;
 use time::macros::format_description;
 use time::{Date, OffsetDateTime};
@@ -57,6 +57,135 @@ impl Statement {
             },
         );
     }
+
+    /// add_struct_param adds a STRUCT bind parameter whose fields are only known at runtime.
+    /// Use add_param with a ToStruct type when the fields are known at compile time.
+    /// ```
+    /// use google_cloud_spanner::statement::{Statement, StructValue};
+    ///
+    /// let mut stmt = Statement::new("SELECT @point.x, @point.y");
+    /// stmt.add_struct_param("point", &StructValue::new().field("x", &1_i64).field("y", &"hello"));
+    /// ```
+    pub fn add_struct_param(&mut self, name: &str, value: &StructValue) {
+        self.param_types.insert(name.to_string(), value.get_type());
+        self.params.insert(
+            name.to_string(),
+            Value {
+                kind: Some(value.to_kind()),
+            },
+        );
+    }
+
+    /// from_safe_template returns a Statement for SQL that is partly built from user input.
+    /// The template is rejected if it contains a string or bytes literal, a comment or a semicolon,
+    /// so user input cannot close a literal, comment out the rest of the query or append a statement.
+    /// Values must be bound as parameters instead; parameters of other types can be added with add_param.
+    /// Backquoted identifiers are not inspected.
+    /// ```
+    /// use google_cloud_spanner::statement::Statement;
+    ///
+    /// let column = "UserId"; // chosen by the user
+    /// let sql = format!("SELECT {column} FROM User WHERE UserId = @UserId");
+    /// let stmt = Statement::from_safe_template(&sql, &[("UserId", "user1")]).unwrap();
+    ///
+    /// let column = "UserId FROM User; DELETE FROM User WHERE true --";
+    /// let sql = format!("SELECT {column} FROM User WHERE UserId = @UserId");
+    /// assert!(Statement::from_safe_template(&sql, &[("UserId", "user1")]).is_err());
+    /// ```
+    pub fn from_safe_template<T>(template: &str, params: &[(&str, T)]) -> Result<Statement, SqlTemplateError>
+    where
+        T: ToKind,
+    {
+        validate_template(template)?;
+        let mut stmt = Statement::new(template);
+        for (name, value) in params {
+            stmt.add_param(name, value);
+        }
+        Ok(stmt)
+    }
+}
+
+/// StructValue is a STRUCT value built field by field, see Statement::add_struct_param.
+#[derive(Clone, Default)]
+pub struct StructValue {
+    fields: Vec<(String, Kind, Type)>,
+}
+
+impl StructValue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// field appends a field, the order of the fields is the order of the STRUCT type.
+    pub fn field<T: ToKind>(mut self, name: &str, value: &T) -> Self {
+        self.fields.push((name.to_string(), value.to_kind(), T::get_type()));
+        self
+    }
+
+    /// to_kind encodes the value as the list of its field values, like ToKind for ToStruct types.
+    pub fn to_kind(&self) -> Kind {
+        Kind::ListValue(ListValue {
+            values: self
+                .fields
+                .iter()
+                .map(|(_, kind, _)| Value {
+                    kind: Some(kind.clone()),
+                })
+                .collect(),
+        })
+    }
+
+    pub fn get_type(&self) -> Type {
+        Type {
+            code: TypeCode::Struct.into(),
+            array_element_type: None,
+            type_annotation: TypeAnnotationCode::Unspecified.into(),
+            struct_type: Some(StructType {
+                fields: self
+                    .fields
+                    .iter()
+                    .map(|(name, _, r#type)| Field {
+                        name: name.clone(),
+                        r#type: Some(r#type.clone()),
+                    })
+                    .collect(),
+            }),
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum SqlTemplateError {
+    #[error("string literal is not allowed in a template at {0}: {1}")]
+    StringLiteral(usize, String),
+    #[error("comment is not allowed in a template at {0}: {1}")]
+    Comment(usize, String),
+    #[error("semicolon is not allowed in a template at {0}: {1}")]
+    Semicolon(usize, String),
+    #[error("unterminated quoted identifier in a template at {0}: {1}")]
+    UnterminatedIdentifier(usize, String),
+}
+
+fn validate_template(template: &str) -> Result<(), SqlTemplateError> {
+    // fragment is the offending part of the template, up to 20 characters.
+    let fragment = |position: usize| template[position..].chars().take(20).collect::<String>();
+    let mut chars = template.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        let next = chars.peek().map(|(_, c)| *c);
+        match (c, next) {
+            ('\'', _) | ('"', _) => return Err(SqlTemplateError::StringLiteral(position, fragment(position))),
+            ('#', _) | ('-', Some('-')) | ('/', Some('*')) => {
+                return Err(SqlTemplateError::Comment(position, fragment(position)))
+            }
+            (';', _) => return Err(SqlTemplateError::Semicolon(position, fragment(position))),
+            // skip the quoted identifier up to its closing backquote
+            ('`', _) if !chars.by_ref().any(|(_, c)| c == '`') => {
+                return Err(SqlTemplateError::UnterminatedIdentifier(position, fragment(position)))
+            }
+            _ => {}
+        }
+    }
+    Ok(())
 }
 
 pub fn single_type<T>(code: T) -> Type
@@ -105,6 +234,30 @@ where
     }
 }
 
+/// struct_values returns the values of to_kinds in the order of get_types, for the encodings that are
+/// positional: STRUCT values, and the rows of a write that share one list of columns.
+/// A field missing from to_kinds is NULL. A name of to_kinds that is not in get_types has no position,
+/// e.g. after a typo in a hand-written ToStruct, so its value is dropped and reported as a warning.
+pub(crate) fn struct_values<T: ToStruct>(to_struct: &T) -> Vec<Value> {
+    let mut kinds: HashMap<&str, Kind> = to_struct.to_kinds().into_iter().collect();
+    let values = T::get_types()
+        .into_iter()
+        .map(|(name, _)| Value {
+            kind: Some(
+                kinds
+                    .remove(name)
+                    .unwrap_or(value::Kind::NullValue(prost_types::NullValue::NullValue.into())),
+            ),
+        })
+        .collect();
+    if !kinds.is_empty() {
+        let mut unknown: Vec<&str> = kinds.into_keys().collect();
+        unknown.sort();
+        tracing::warn!("ToStruct::to_kinds returns fields missing from get_types, they are dropped: {unknown:?}");
+    }
+    values
+}
+
 impl ToKind for String {
     fn to_kind(&self) -> Kind {
         StringValue(self.clone())
@@ -206,6 +359,60 @@ impl ToKind for BigDecimal {
     }
 }
 
+impl ToKind for std::net::IpAddr {
+    fn to_kind(&self) -> Kind {
+        self.to_string().to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::String)
+    }
+}
+
+impl ToKind for std::net::SocketAddr {
+    fn to_kind(&self) -> Kind {
+        self.to_string().to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::String)
+    }
+}
+
+/// UUIDs are stored as STRING columns in their hyphenated form, as returned by GENERATE_UUID().
+#[cfg(feature = "uuid")]
+impl ToKind for uuid::Uuid {
+    fn to_kind(&self) -> Kind {
+        self.to_string().to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::String)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: chrono::TimeZone> ToKind for chrono::DateTime<Tz>
+where
+    Tz::Offset: std::fmt::Display,
+{
+    fn to_kind(&self) -> Kind {
+        self.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true).to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Timestamp)
+    }
+}
+
+/// JSON values are sent as their serialized string. `serde_json::Value::Null` is the JSON literal `null`,
+/// use `Option::<serde_json::Value>::None` to write SQL NULL.
+#[cfg(feature = "json")]
+impl ToKind for serde_json::Value {
+    fn to_kind(&self) -> Kind {
+        self.to_string().to_kind()
+    }
+    fn get_type() -> Type {
+        single_type(TypeCode::Json)
+    }
+}
+
 impl ToKind for ::prost_types::Timestamp {
     fn to_kind(&self) -> Kind {
         // The protobuf timestamp type should be formatted in RFC3339
@@ -227,11 +434,10 @@ where
     T: ToStruct,
 {
     fn to_kind(&self) -> Kind {
-        let mut fields = BTreeMap::<String, Value>::default();
-        self.to_kinds().into_iter().for_each(|e| {
-            fields.insert(e.0.into(), Value { kind: Some(e.1) });
-        });
-        Kind::StructValue(Struct { fields })
+        // STRUCT is encoded as a list of the field values, in the order of the fields in the struct type.
+        Kind::ListValue(ListValue {
+            values: struct_values(self),
+        })
     }
     fn get_type() -> Type {
         Type {
@@ -308,10 +514,89 @@ where
 
 #[cfg(test)]
 mod test {
-    use crate::statement::ToKind;
+    use crate::statement::{
+        struct_values, validate_template, Kinds, SqlTemplateError, Statement, StructValue, ToKind, ToStruct, Types,
+    };
     use prost_types::value::Kind;
+    use prost_types::{ListValue, Value};
     use time::OffsetDateTime;
 
+    struct Point {
+        x: i64,
+        y: Option<String>,
+    }
+
+    impl ToStruct for Point {
+        fn to_kinds(&self) -> Kinds {
+            vec![("y", self.y.to_kind()), ("x", self.x.to_kind())]
+        }
+
+        fn get_types() -> Types {
+            vec![
+                ("x", i64::get_type()),
+                ("y", String::get_type()),
+                ("z", String::get_type()),
+            ]
+        }
+    }
+
+    struct Misspelled;
+
+    impl ToStruct for Misspelled {
+        fn to_kinds(&self) -> Kinds {
+            vec![("X", 1_i64.to_kind())]
+        }
+
+        fn get_types() -> Types {
+            vec![("x", i64::get_type())]
+        }
+    }
+
+    #[test]
+    fn test_struct_values() {
+        let point = Point {
+            x: 1,
+            y: Some("a".to_string()),
+        };
+        let kinds: Vec<Option<Kind>> = struct_values(&point).into_iter().map(|v| v.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Some(Kind::StringValue("1".to_string())),
+                Some(Kind::StringValue("a".to_string())),
+                Some(Kind::NullValue(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_struct_values_unknown_field() {
+        let kinds: Vec<Option<Kind>> = struct_values(&Misspelled).into_iter().map(|v| v.kind).collect();
+        assert_eq!(kinds, vec![Some(Kind::NullValue(0))]);
+    }
+
+    #[test]
+    fn test_struct_value() {
+        let value = StructValue::new().field("y", &"a").field("x", &1_i64);
+        assert_eq!(
+            value.to_kind(),
+            Kind::ListValue(ListValue {
+                values: vec![
+                    Value {
+                        kind: Some(Kind::StringValue("a".to_string())),
+                    },
+                    Value {
+                        kind: Some(Kind::StringValue("1".to_string())),
+                    },
+                ],
+            })
+        );
+        let fields = value.get_type().struct_type.unwrap().fields;
+        let names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["y", "x"]);
+        assert_eq!(fields[1].r#type, Some(i64::get_type()));
+    }
+
     // Test that prost's to_kind implementation works as expected.
     #[test]
     fn prost_timestamp_to_kind_works() {
@@ -325,4 +610,38 @@ mod test {
         // Prost's Timestamp type and OffsetDateTime should have the same representation in spanner
         assert_eq!(prost_types::Timestamp::get_type(), OffsetDateTime::get_type());
     }
+
+    #[test]
+    fn test_validate_template() {
+        let valid = [
+            "SELECT UserId FROM User WHERE UserId = @UserId",
+            "SELECT `Order`, a - b, a / b FROM `Table;Name` WHERE x > @x",
+        ];
+        for template in valid {
+            assert_eq!(validate_template(template), Ok(()), "{template}");
+        }
+
+        assert_eq!(
+            validate_template("SELECT * FROM User WHERE UserId = 'a' OR 1=1"),
+            Err(SqlTemplateError::StringLiteral(34, "'a' OR 1=1".to_string()))
+        );
+        assert!(matches!(
+            validate_template("SELECT * FROM User WHERE b\"x\""),
+            Err(SqlTemplateError::StringLiteral(_, _))
+        ));
+        for template in ["SELECT 1 -- x", "SELECT 1 # x", "SELECT /* x */ 1"] {
+            assert!(matches!(validate_template(template), Err(SqlTemplateError::Comment(_, _))));
+        }
+        assert_eq!(
+            validate_template("SELECT 1; DELETE FROM User WHERE true"),
+            Err(SqlTemplateError::Semicolon(8, "; DELETE FROM User W".to_string()))
+        );
+        assert!(matches!(
+            validate_template("SELECT `a FROM User"),
+            Err(SqlTemplateError::UnterminatedIdentifier(7, _))
+        ));
+
+        let stmt = Statement::from_safe_template("SELECT @a, @b", &[("a", 1), ("b", 2)]).unwrap();
+        assert_eq!(stmt.params.len(), 2);
+    }
 }
@@ -7,8 +7,8 @@ use google_cloud_gax::grpc::Status;
 use google_cloud_gax::retry::RetrySetting;
 use google_cloud_googleapis::spanner::v1::request_options::Priority;
 use google_cloud_googleapis::spanner::v1::{
-    execute_sql_request::QueryMode, execute_sql_request::QueryOptions as ExecuteQueryOptions, ExecuteSqlRequest,
-    ReadRequest, RequestOptions, TransactionSelector,
+    execute_sql_request::QueryMode, execute_sql_request::QueryOptions as ExecuteQueryOptions, transaction_selector,
+    ExecuteSqlRequest, ReadRequest, RequestOptions, TransactionSelector,
 };
 
 use crate::key::{Key, KeySet};
@@ -21,6 +21,14 @@ use crate::statement::Statement;
 pub struct CallOptions {
     /// Priority is the RPC priority to use for the read operation.
     pub priority: Option<Priority>,
+    /// request_tag labels the request in the query and read statistics of SPANNER_SYS.
+    /// Cloud Spanner ignores it for requests other than reads, queries and DML.
+    pub request_tag: Option<String>,
+    /// transaction_tag labels a read-write transaction in the transaction statistics of SPANNER_SYS.
+    /// It is taken from the options used to begin the transaction and sent with every request of it.
+    /// Read-only and single-use transactions do not take it from their begin options, because Cloud Spanner
+    /// ignores the transaction tag outside of read-write transactions. Use request_tag for their reads and queries.
+    pub transaction_tag: Option<String>,
     pub retry: Option<RetrySetting>,
 }
 
@@ -30,7 +38,7 @@ pub struct ReadOptions {
     /// that are part of the index key, part of the primary key, or stored in the
     /// index due to a STORING clause in the index definition.
     pub index: String,
-    /// The maximum number of rows to read. A limit value less than 1 means no limit.
+    /// The maximum number of rows to read. Zero means no limit, negative values are rejected.
     pub limit: i64,
     pub call_options: CallOptions,
 }
@@ -103,17 +111,30 @@ pub struct Transaction {
     // for returning ownership of session on before destroy
     pub(crate) sequence_number: AtomicI64,
     pub(crate) transaction_selector: TransactionSelector,
+    pub(crate) transaction_tag: Option<String>,
 }
 
 impl Transaction {
-    pub(crate) fn create_request_options(priority: Option<Priority>) -> Option<RequestOptions> {
-        priority.map(|s| RequestOptions {
-            priority: s.into(),
-            request_tag: "".to_string(),
-            transaction_tag: "".to_string(),
+    pub(crate) fn create_request_options(options: &CallOptions) -> Option<RequestOptions> {
+        if options.priority.is_none() && options.request_tag.is_none() && options.transaction_tag.is_none() {
+            return None;
+        }
+        Some(RequestOptions {
+            priority: options.priority.unwrap_or(Priority::Unspecified).into(),
+            request_tag: options.request_tag.clone().unwrap_or_default(),
+            transaction_tag: options.transaction_tag.clone().unwrap_or_default(),
         })
     }
 
+    /// request_options creates the RequestOptions of a request in this transaction, tagged with its transaction tag.
+    pub(crate) fn request_options(&self, options: &CallOptions) -> Option<RequestOptions> {
+        let mut request_options = Transaction::create_request_options(options);
+        if let Some(tag) = &self.transaction_tag {
+            request_options.get_or_insert_with(Default::default).transaction_tag = tag.clone();
+        }
+        request_options
+    }
+
     /// query executes a query against the database. It returns a RowIterator for
     /// retrieving the resulting rows.
     ///
@@ -144,7 +165,7 @@ impl Transaction {
             partition_token: vec![],
             seqno: 0,
             query_options: options.optimizer_options,
-            request_options: Transaction::create_request_options(options.call_options.priority),
+            request_options: self.request_options(&options.call_options),
             data_boost_enabled: false,
         };
         let session = self.session.as_mut().unwrap().deref_mut();
@@ -193,6 +214,12 @@ impl Transaction {
         key_set: impl Into<KeySet>,
         options: ReadOptions,
     ) -> Result<RowIterator<'_, impl Reader>, Status> {
+        if options.limit < 0 {
+            return Err(Status::invalid_argument(format!(
+                "limit must not be negative: {}",
+                options.limit
+            )));
+        }
         let request = ReadRequest {
             session: self.get_session_name(),
             transaction: Some(self.transaction_selector.clone()),
@@ -203,7 +230,7 @@ impl Transaction {
             limit: options.limit,
             resume_token: vec![],
             partition_token: vec![],
-            request_options: Transaction::create_request_options(options.call_options.priority),
+            request_options: self.request_options(&options.call_options),
             data_boost_enabled: false,
         };
 
@@ -245,6 +272,21 @@ impl Transaction {
         reader.next().await
     }
 
+    /// session_name returns the resource name of the session the transaction runs in.
+    /// Cloud Audit Logs record it as the resource name of data access entries.
+    pub fn session_name(&self) -> &str {
+        &self.session.as_ref().unwrap().session.name
+    }
+
+    /// transaction_id returns the id assigned by Cloud Spanner, or None for a single-use transaction.
+    /// Cloud Audit Logs record it base64 encoded in the request of data access entries.
+    pub fn transaction_id(&self) -> Option<&[u8]> {
+        match &self.transaction_selector.selector {
+            Some(transaction_selector::Selector::Id(id)) => Some(id),
+            _ => None,
+        }
+    }
+
     pub(crate) fn get_session_name(&self) -> String {
         return self.session.as_ref().unwrap().session.name.to_string();
     }
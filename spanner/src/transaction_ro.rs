@@ -2,7 +2,10 @@ use std::ops::{Deref, DerefMut};
 use std::sync::atomic::AtomicI64;
 use std::time::SystemTime;
 
+use futures_util::stream::{self, Stream};
 use time::OffsetDateTime;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
 
 use google_cloud_gax::grpc::Status;
 use google_cloud_googleapis::spanner::v1::{
@@ -12,6 +15,7 @@ use google_cloud_googleapis::spanner::v1::{
 
 use crate::key::KeySet;
 use crate::reader::{Reader, RowIterator, StatementReader, TableReader};
+use crate::row::Row;
 use crate::session::ManagedSession;
 use crate::statement::Statement;
 use crate::transaction::{CallOptions, QueryOptions, ReadOptions, Transaction};
@@ -61,6 +65,7 @@ impl ReadOnlyTransaction {
                         mode: Some(transaction_options::Mode::ReadOnly(tb.into())),
                     })),
                 },
+                transaction_tag: None,
             },
             rts: None,
         })
@@ -77,7 +82,7 @@ impl ReadOnlyTransaction {
             options: Some(TransactionOptions {
                 mode: Some(transaction_options::Mode::ReadOnly(tb.into())),
             }),
-            request_options: Transaction::create_request_options(options.priority),
+            request_options: Transaction::create_request_options(&options),
         };
 
         let result = session.spanner_client.begin_transaction(request, options.retry).await;
@@ -93,6 +98,7 @@ impl ReadOnlyTransaction {
                         transaction_selector: TransactionSelector {
                             selector: Some(transaction_selector::Selector::Id(tx.id)),
                         },
+                        transaction_tag: None,
                     },
                     rts: Some(OffsetDateTime::from(st)),
                 })
@@ -100,6 +106,60 @@ impl ReadOnlyTransaction {
             Err(e) => Err(e),
         }
     }
+
+    /// read_timestamp returns the timestamp of the snapshot of a transaction started with
+    /// Client::read_only_transaction. Use it with TimestampBound::read_timestamp to read the same
+    /// snapshot again later. Single-use transactions return None, see RowIterator::read_timestamp.
+    pub fn read_timestamp(&self) -> Option<OffsetDateTime> {
+        self.rts
+    }
+
+    /// query_prefetched runs the query in a spawned task that reads up to `buffer_rows` rows ahead
+    /// of the returned stream, so that receiving the next rows overlaps with processing the current ones.
+    /// The task owns the transaction and finishes when the rows are exhausted, on the first error,
+    /// or when the stream is dropped.
+    /// ```
+    /// use futures_util::StreamExt;
+    /// use google_cloud_spanner::client::Error;
+    /// use google_cloud_spanner::statement::Statement;
+    /// use google_cloud_spanner::transaction_ro::ReadOnlyTransaction;
+    ///
+    /// async fn run(tx: ReadOnlyTransaction) -> Result<(), Error> {
+    ///     let (rows, task) = tx.query_prefetched(Statement::new("SELECT UserId FROM User"), 100);
+    ///     let mut rows = Box::pin(rows);
+    ///     while let Some(row) = rows.next().await {
+    ///         let user_id: String = row?.column(0)?;
+    ///     }
+    ///     Ok(task.await.unwrap()?)
+    /// }
+    /// ```
+    pub fn query_prefetched(
+        mut self,
+        statement: Statement,
+        buffer_rows: usize,
+    ) -> (impl Stream<Item = Result<Row, Status>>, JoinHandle<Result<(), Status>>) {
+        let (sender, receiver) = mpsc::channel(buffer_rows.max(1));
+        let task = tokio::spawn(async move {
+            let result: Result<(), Status> = async {
+                let mut iter = self.query(statement).await?;
+                while let Some(row) = iter.next().await? {
+                    if sender.send(Ok(row)).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+            .await;
+            if let Err(e) = &result {
+                let _ = sender.send(Err(e.clone())).await;
+            }
+            result
+        });
+        let rows = stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|row| (row, receiver))
+        });
+        (rows, task)
+    }
 }
 
 pub struct Partition<T: Reader> {
@@ -157,6 +217,26 @@ impl BatchReadOnlyTransaction {
     /// the database. These partitions can be executed across multiple processes,
     /// even across different machines. The partition size and count hints can be
     /// configured using PartitionOptions.
+    ///
+    /// To spread a range of keys over N workers, pass the KeyRange with max_partitions = N.
+    /// Spanner splits the range by the actual data distribution rather than evenly by key.
+    /// ```
+    /// use google_cloud_googleapis::spanner::v1::PartitionOptions;
+    /// use google_cloud_spanner::key::{Key, KeyRange, RangeKind};
+    /// use google_cloud_spanner::transaction::ReadOptions;
+    /// use google_cloud_spanner::transaction_ro::BatchReadOnlyTransaction;
+    /// use google_cloud_gax::grpc::Status;
+    ///
+    /// async fn run(tx: &mut BatchReadOnlyTransaction) -> Result<(), Status> {
+    ///     let range = KeyRange::new(Key::new(&"user_a"), Key::new(&"user_m"), RangeKind::ClosedOpen);
+    ///     let po = PartitionOptions { partition_size_bytes: 0, max_partitions: 8 };
+    ///     let partitions = tx
+    ///         .partition_read_with_option("User", &["UserId"], range, Some(po), ReadOptions::default(), false)
+    ///         .await?;
+    ///     // hand each partition to a worker
+    ///     Ok(())
+    /// }
+    /// ```
     pub async fn partition_read_with_option(
         &mut self,
         table: &str,
@@ -166,6 +246,9 @@ impl BatchReadOnlyTransaction {
         ro: ReadOptions,
         data_boost_enabled: bool,
     ) -> Result<Vec<Partition<TableReader>>, Status> {
+        if ro.limit < 0 {
+            return Err(Status::invalid_argument(format!("limit must not be negative: {}", ro.limit)));
+        }
         let columns: Vec<String> = columns.iter().map(|x| x.to_string()).collect();
         let inner_keyset = keys.into().inner;
         let request = PartitionReadRequest {
@@ -177,6 +260,7 @@ impl BatchReadOnlyTransaction {
             key_set: Some(inner_keyset.clone()),
             partition_options: po,
         };
+        let request_options = Transaction::create_request_options(&ro.call_options);
         let result = match self
             .as_mut_session()
             .spanner_client
@@ -199,7 +283,7 @@ impl BatchReadOnlyTransaction {
                             limit: ro.limit,
                             resume_token: vec![],
                             partition_token: x.partition_token,
-                            request_options: Transaction::create_request_options(ro.call_options.priority),
+                            request_options: request_options.clone(),
                             data_boost_enabled,
                         },
                     },
@@ -260,7 +344,7 @@ impl BatchReadOnlyTransaction {
                             partition_token: x.partition_token,
                             seqno: 0,
                             query_options: qo.optimizer_options.clone(),
-                            request_options: Transaction::create_request_options(qo.call_options.priority),
+                            request_options: Transaction::create_request_options(&qo.call_options),
                             data_boost_enabled,
                         },
                     },
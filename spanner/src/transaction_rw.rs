@@ -1,18 +1,23 @@
+use std::future::Future;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::sync::atomic::{AtomicI64, Ordering};
 
+use prost::Message;
 use prost_types::Struct;
 
 use google_cloud_gax::grpc::{Code, Status};
 use google_cloud_gax::retry::{RetrySetting, TryAs};
+use google_cloud_googleapis::rpc::Status as RpcStatus;
 use google_cloud_googleapis::spanner::v1::commit_request::Transaction::TransactionId;
+use google_cloud_googleapis::spanner::v1::transaction_options::read_write::ReadLockMode;
 use google_cloud_googleapis::spanner::v1::{
     commit_request, execute_batch_dml_request, result_set_stats, transaction_options, transaction_selector,
     BeginTransactionRequest, CommitRequest, CommitResponse, ExecuteBatchDmlRequest, ExecuteSqlRequest, Mutation,
     ResultSetStats, RollbackRequest, TransactionOptions, TransactionSelector,
 };
 
+use crate::reader::{Reader, RowIterator, StatementReader};
 use crate::session::ManagedSession;
 use crate::statement::Statement;
 use crate::transaction::{CallOptions, QueryOptions, Transaction};
@@ -101,11 +106,69 @@ pub struct BeginError {
     pub session: ManagedSession,
 }
 
+/// TransactionContext identifies the read-write transaction the current task runs in.
+/// It is set by `with_transaction_context` and read with `TransactionContext::current`.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TransactionContext {
+    pub session_name: String,
+    pub transaction_id: Vec<u8>,
+}
+
+tokio::task_local! {
+    static SPANNER_TX_CONTEXT: TransactionContext;
+}
+
+impl TransactionContext {
+    /// current returns the context of the enclosing `with_transaction_context`, if any.
+    /// Tasks spawned from within the future do not inherit it.
+    pub fn current() -> Option<TransactionContext> {
+        SPANNER_TX_CONTEXT.try_with(|context| context.clone()).ok()
+    }
+}
+
+/// with_transaction_context makes the context of `tx` available to `future` and everything it awaits,
+/// so that code deep in a call stack, like middleware or logging, can tell which transaction it is part of
+/// without the transaction being passed through every function.
+/// ```
+/// use google_cloud_spanner::client::{Client, Error};
+/// use google_cloud_spanner::transaction_rw::{with_transaction_context, TransactionContext};
+///
+/// async fn audit() {
+///     if let Some(context) = TransactionContext::current() {
+///         println!("running in {}", context.session_name);
+///     }
+/// }
+///
+/// async fn run(client: Client) -> Result<(), Error> {
+///     let mut tx = client.begin_read_write_transaction().await?;
+///     with_transaction_context(&tx, audit()).await;
+///     let _ = tx.end(Ok::<(), Error>(()), None).await?;
+///     Ok(())
+/// }
+/// ```
+pub fn with_transaction_context<F: Future>(tx: &ReadWriteTransaction, future: F) -> impl Future<Output = F::Output> {
+    let context = TransactionContext {
+        session_name: tx.session_name().to_string(),
+        transaction_id: tx.tx_id.clone(),
+    };
+    SPANNER_TX_CONTEXT.scope(context, future)
+}
+
 impl ReadWriteTransaction {
     pub async fn begin(session: ManagedSession, options: CallOptions) -> Result<ReadWriteTransaction, BeginError> {
+        ReadWriteTransaction::begin_with_read_lock_mode(session, options, ReadLockMode::Unspecified).await
+    }
+
+    pub async fn begin_with_read_lock_mode(
+        session: ManagedSession,
+        options: CallOptions,
+        read_lock_mode: ReadLockMode,
+    ) -> Result<ReadWriteTransaction, BeginError> {
         ReadWriteTransaction::begin_internal(
             session,
-            transaction_options::Mode::ReadWrite(transaction_options::ReadWrite::default()),
+            transaction_options::Mode::ReadWrite(transaction_options::ReadWrite {
+                read_lock_mode: read_lock_mode.into(),
+            }),
             options,
         )
         .await
@@ -131,8 +194,9 @@ impl ReadWriteTransaction {
         let request = BeginTransactionRequest {
             session: session.session.name.to_string(),
             options: Some(TransactionOptions { mode: Some(mode) }),
-            request_options: Transaction::create_request_options(options.priority),
+            request_options: Transaction::create_request_options(&options),
         };
+        let transaction_tag = options.transaction_tag.clone();
         let result = session.spanner_client.begin_transaction(request, options.retry).await;
         let response = match session.invalidate_if_needed(result).await {
             Ok(response) => response,
@@ -148,6 +212,7 @@ impl ReadWriteTransaction {
                 transaction_selector: TransactionSelector {
                     selector: Some(transaction_selector::Selector::Id(tx.id.clone())),
                 },
+                transaction_tag,
             },
             tx_id: tx.id,
             wb: vec![],
@@ -158,6 +223,21 @@ impl ReadWriteTransaction {
         self.wb.extend_from_slice(&ms)
     }
 
+    /// buffered_mutations returns the mutations that will be sent with the commit.
+    pub fn buffered_mutations(&self) -> &[Mutation] {
+        &self.wb
+    }
+
+    pub fn buffered_mutation_count(&self) -> usize {
+        self.wb.len()
+    }
+
+    /// estimated_commit_size_bytes returns the encoded size of the buffered mutations.
+    /// The commit request is slightly larger, it also carries the session and the transaction id.
+    pub fn estimated_commit_size_bytes(&self) -> usize {
+        self.wb.iter().map(|m| m.encoded_len()).sum()
+    }
+
     pub async fn update(&mut self, stmt: Statement) -> Result<i64, Status> {
         self.update_with_option(stmt, QueryOptions::default()).await
     }
@@ -175,7 +255,7 @@ impl ReadWriteTransaction {
             partition_token: vec![],
             seqno: self.sequence_number.fetch_add(1, Ordering::Relaxed),
             query_options: options.optimizer_options,
-            request_options: Transaction::create_request_options(options.call_options.priority),
+            request_options: self.request_options(&options.call_options),
         };
 
         let session = self.as_mut_session();
@@ -187,6 +267,57 @@ impl ReadWriteTransaction {
         Ok(extract_row_count(response.into_inner().stats))
     }
 
+    /// update_returning executes a DML statement with a THEN RETURN clause
+    /// and returns a RowIterator for the returned rows.
+    /// ```
+    /// use google_cloud_spanner::client::Error;
+    /// use google_cloud_spanner::statement::Statement;
+    /// use google_cloud_spanner::transaction_rw::ReadWriteTransaction;
+    ///
+    /// async fn run(tx: &mut ReadWriteTransaction) -> Result<Vec<i64>, Error> {
+    ///     let stmt = Statement::new("UPDATE UserCharacter SET Level = Level + 1 WHERE UserId = 'user1' THEN RETURN Level");
+    ///     let mut iter = tx.update_returning(stmt).await?;
+    ///     let mut levels = vec![];
+    ///     while let Some(row) = iter.next().await? {
+    ///         levels.push(row.column_by_name("Level")?);
+    ///     }
+    ///     Ok(levels)
+    /// }
+    /// ```
+    pub async fn update_returning(&mut self, stmt: Statement) -> Result<RowIterator<'_, impl Reader>, Status> {
+        self.update_returning_with_option(stmt, QueryOptions::default()).await
+    }
+
+    pub async fn update_returning_with_option(
+        &mut self,
+        stmt: Statement,
+        options: QueryOptions,
+    ) -> Result<RowIterator<'_, impl Reader>, Status> {
+        let request = ExecuteSqlRequest {
+            session: self.get_session_name(),
+            transaction: Some(self.transaction_selector.clone()),
+            sql: stmt.sql,
+            data_boost_enabled: false,
+            params: Some(prost_types::Struct { fields: stmt.params }),
+            param_types: stmt.param_types,
+            resume_token: vec![],
+            query_mode: options.mode.into(),
+            partition_token: vec![],
+            seqno: self.sequence_number.fetch_add(1, Ordering::Relaxed),
+            query_options: options.optimizer_options,
+            request_options: self.request_options(&options.call_options),
+        };
+        let session = self.as_mut_session().deref_mut();
+        // DML must not be executed again when the stream is interrupted.
+        let reader = StatementReader {
+            enable_resume: false,
+            request,
+        };
+        RowIterator::new(session, reader, Some(options.call_options)).await
+    }
+
+    /// batch_update executes the DML statements in a single round trip and returns the number of
+    /// rows modified by each. Statements run in order and stop at the first failure, whose error is returned.
     pub async fn batch_update(&mut self, stmt: Vec<Statement>) -> Result<Vec<i64>, Status> {
         self.batch_update_with_option(stmt, QueryOptions::default()).await
     }
@@ -196,11 +327,30 @@ impl ReadWriteTransaction {
         stmt: Vec<Statement>,
         options: QueryOptions,
     ) -> Result<Vec<i64>, Status> {
+        let (row_counts, status) = self.batch_update_partial_with_option(stmt, options).await?;
+        match status {
+            Some(status) => Err(status),
+            None => Ok(row_counts),
+        }
+    }
+
+    /// batch_update_partial is like batch_update, but when statement N fails it returns
+    /// the row counts of statements 0..N together with the error of statement N.
+    pub async fn batch_update_partial(&mut self, stmt: Vec<Statement>) -> Result<(Vec<i64>, Option<Status>), Status> {
+        self.batch_update_partial_with_option(stmt, QueryOptions::default())
+            .await
+    }
+
+    pub async fn batch_update_partial_with_option(
+        &mut self,
+        stmt: Vec<Statement>,
+        options: QueryOptions,
+    ) -> Result<(Vec<i64>, Option<Status>), Status> {
         let request = ExecuteBatchDmlRequest {
             session: self.get_session_name(),
             transaction: Some(self.transaction_selector.clone()),
             seqno: self.sequence_number.fetch_add(1, Ordering::Relaxed),
-            request_options: Transaction::create_request_options(options.call_options.priority),
+            request_options: self.request_options(&options.call_options),
             statements: stmt
                 .into_iter()
                 .map(|x| execute_batch_dml_request::Statement {
@@ -216,13 +366,14 @@ impl ReadWriteTransaction {
             .spanner_client
             .execute_batch_dml(request, options.call_options.retry)
             .await;
-        let response = session.invalidate_if_needed(result).await?;
-        Ok(response
-            .into_inner()
+        let response = session.invalidate_if_needed(result).await?.into_inner();
+        let row_counts = response
             .result_sets
             .into_iter()
             .map(|x| extract_row_count(x.stats))
-            .collect())
+            .collect();
+        let status = response.status.filter(|s| s.code != Code::Ok as i32).map(batch_status);
+        Ok((row_counts, status))
     }
 
     pub async fn end<S, E>(
@@ -296,7 +447,10 @@ impl ReadWriteTransaction {
         };
     }
 
-    pub(crate) async fn commit(&mut self, options: CommitOptions) -> Result<CommitResponse, Status> {
+    pub(crate) async fn commit(&mut self, mut options: CommitOptions) -> Result<CommitResponse, Status> {
+        if self.transaction_tag.is_some() {
+            options.call_options.transaction_tag = self.transaction_tag.clone();
+        }
         let tx_id = self.tx_id.clone();
         let mutations = self.wb.to_vec();
         let session = self.as_mut_session();
@@ -325,7 +479,7 @@ pub(crate) async fn commit(
         session: session.session.name.to_string(),
         mutations: ms,
         transaction: Some(tx),
-        request_options: Transaction::create_request_options(commit_options.call_options.priority),
+        request_options: Transaction::create_request_options(&commit_options.call_options),
         return_commit_stats: commit_options.return_commit_stats,
     };
     let result = session
@@ -339,6 +493,12 @@ pub(crate) async fn commit(
     }
 }
 
+/// batch_status converts the status of the failed statement of a batch, keeping its details
+/// so that the RetryInfo of an ABORTED status is still honored.
+fn batch_status(status: RpcStatus) -> Status {
+    Status::with_details(Code::from(status.code), status.message.clone(), status.encode_to_vec().into())
+}
+
 fn extract_row_count(rs: Option<ResultSetStats>) -> i64 {
     match rs {
         Some(o) => match o.row_count {
@@ -351,3 +511,34 @@ fn extract_row_count(rs: Option<ResultSetStats>) -> i64 {
         None => 0,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use prost::Message;
+
+    use google_cloud_gax::grpc::Code;
+    use google_cloud_googleapis::rpc::Status as RpcStatus;
+
+    use crate::retry::{retry_delay, RetryInfo, RETRY_INFO_TYPE};
+    use crate::transaction_rw::batch_status;
+
+    #[test]
+    fn test_batch_status() {
+        let retry_info = RetryInfo {
+            retry_delay: Some(prost_types::Duration { seconds: 1, nanos: 0 }),
+        };
+        let status = batch_status(RpcStatus {
+            code: Code::Aborted as i32,
+            message: "aborted".to_string(),
+            details: vec![prost_types::Any {
+                type_url: RETRY_INFO_TYPE.to_string(),
+                value: retry_info.encode_to_vec(),
+            }],
+        });
+        assert_eq!(status.code(), Code::Aborted);
+        assert_eq!(status.message(), "aborted");
+        assert_eq!(retry_delay(&status), Some(Duration::from_secs(1)));
+    }
+}
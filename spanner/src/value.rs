@@ -1,9 +1,12 @@
 use std::ops::Deref;
+use std::str::FromStr;
 use std::time::Duration;
 
 use google_cloud_googleapis::spanner::v1::transaction_options::read_only::TimestampBound as InternalTimestampBound;
 use google_cloud_googleapis::spanner::v1::transaction_options::ReadOnly;
 
+use crate::bigdecimal::{BigDecimal, RoundingMode, Zero};
+
 #[derive(Clone, PartialEq, Eq)]
 pub struct Timestamp {
     /// Represents seconds of UTC time since Unix epoch
@@ -35,6 +38,61 @@ impl From<prost_types::Timestamp> for Timestamp {
     }
 }
 
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+#[error("timestamp is out of range: seconds={0}, nanos={1}")]
+pub struct TimestampOutOfRange(pub i64, pub i32);
+
+impl Timestamp {
+    /// valid_nanos returns the nanos, or an error if they are not from 0 to 999,999,999.
+    fn valid_nanos(&self) -> Result<i32, TimestampOutOfRange> {
+        if (0..1_000_000_000).contains(&self.nanos) {
+            Ok(self.nanos)
+        } else {
+            Err(TimestampOutOfRange(self.seconds, self.nanos))
+        }
+    }
+}
+
+/// Cloud Spanner timestamps range from year 1 to 9999, which OffsetDateTime always covers.
+/// Values outside of it can only be built by hand, and are rejected.
+impl TryFrom<Timestamp> for time::OffsetDateTime {
+    type Error = TimestampOutOfRange;
+
+    fn try_from(t: Timestamp) -> Result<Self, Self::Error> {
+        let nanos = t.seconds as i128 * 1_000_000_000 + t.valid_nanos()? as i128;
+        time::OffsetDateTime::from_unix_timestamp_nanos(nanos).map_err(|_| TimestampOutOfRange(t.seconds, t.nanos))
+    }
+}
+
+impl From<time::OffsetDateTime> for Timestamp {
+    fn from(t: time::OffsetDateTime) -> Self {
+        Timestamp {
+            seconds: t.unix_timestamp(),
+            nanos: t.nanosecond() as i32,
+        }
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl TryFrom<Timestamp> for chrono::DateTime<chrono::Utc> {
+    type Error = TimestampOutOfRange;
+
+    fn try_from(t: Timestamp) -> Result<Self, Self::Error> {
+        chrono::DateTime::from_timestamp(t.seconds, t.valid_nanos()? as u32)
+            .ok_or(TimestampOutOfRange(t.seconds, t.nanos))
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::DateTime<chrono::Utc>> for Timestamp {
+    fn from(t: chrono::DateTime<chrono::Utc>) -> Self {
+        Timestamp {
+            seconds: t.timestamp(),
+            nanos: t.timestamp_subsec_nanos() as i32,
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, PartialOrd, Ord, Copy, Clone)]
 pub struct CommitTimestamp {
     pub(crate) timestamp: time::OffsetDateTime,
@@ -109,3 +167,146 @@ impl From<TimestampBound> for ReadOnly {
         }
     }
 }
+
+#[derive(thiserror::Error, Debug, PartialEq)]
+pub enum NumericError {
+    #[error("value is out of the NUMERIC range: {0}")]
+    Overflow(BigDecimal),
+    #[error("value has more than 9 fractional digits: {0}")]
+    Scale(BigDecimal),
+    #[error("division by zero")]
+    DivisionByZero,
+}
+
+/// Numeric checks BigDecimal values against the range of the NUMERIC type,
+/// which holds 29 integer digits and 9 fractional digits.
+/// Values outside of it are otherwise only rejected when they are committed.
+/// ```
+/// use std::str::FromStr;
+/// use google_cloud_spanner::bigdecimal::BigDecimal;
+/// use google_cloud_spanner::value::{Numeric, NumericError};
+///
+/// let one = BigDecimal::from(1);
+/// assert!(matches!(Numeric::add(&Numeric::max(), &one), Err(NumericError::Overflow(_))));
+/// assert_eq!(Numeric::div(&one, &BigDecimal::from(3)).unwrap(), BigDecimal::from_str("0.333333333").unwrap());
+/// ```
+pub struct Numeric;
+
+impl Numeric {
+    pub const MAX: &'static str = "99999999999999999999999999999.999999999";
+    pub const MIN: &'static str = "-99999999999999999999999999999.999999999";
+    pub const SCALE: i64 = 9;
+
+    pub fn max() -> BigDecimal {
+        BigDecimal::from_str(Self::MAX).unwrap()
+    }
+
+    pub fn min() -> BigDecimal {
+        BigDecimal::from_str(Self::MIN).unwrap()
+    }
+
+    /// check returns the value if it can be written to a NUMERIC column as is.
+    pub fn check(value: BigDecimal) -> Result<BigDecimal, NumericError> {
+        if value > Self::max() || value < Self::min() {
+            return Err(NumericError::Overflow(value));
+        }
+        if value.with_scale(Self::SCALE) != value {
+            return Err(NumericError::Scale(value));
+        }
+        Ok(value)
+    }
+
+    pub fn add(a: &BigDecimal, b: &BigDecimal) -> Result<BigDecimal, NumericError> {
+        Self::check(a + b)
+    }
+
+    pub fn sub(a: &BigDecimal, b: &BigDecimal) -> Result<BigDecimal, NumericError> {
+        Self::check(a - b)
+    }
+
+    /// mul rounds the product half away from zero to 9 fractional digits, as Spanner does.
+    pub fn mul(a: &BigDecimal, b: &BigDecimal) -> Result<BigDecimal, NumericError> {
+        Self::check(Self::round(a * b))
+    }
+
+    /// div rounds the quotient half away from zero to 9 fractional digits, as Spanner does.
+    pub fn div(a: &BigDecimal, b: &BigDecimal) -> Result<BigDecimal, NumericError> {
+        if b.is_zero() {
+            return Err(NumericError::DivisionByZero);
+        }
+        Self::check(Self::round(a / b))
+    }
+
+    fn round(value: BigDecimal) -> BigDecimal {
+        value.with_scale_round(Self::SCALE, RoundingMode::HalfUp)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use crate::bigdecimal::BigDecimal;
+    use crate::value::{Numeric, NumericError, Timestamp, TimestampOutOfRange};
+
+    #[test]
+    fn test_timestamp_conversion() {
+        let dt = time::macros::datetime!(1969-12-31 23:59:58.5 UTC);
+        let ts = Timestamp::from(dt);
+        assert_eq!((ts.seconds, ts.nanos), (-2, 500_000_000));
+        assert_eq!(time::OffsetDateTime::try_from(ts.clone()).unwrap(), dt);
+        #[cfg(feature = "chrono")]
+        {
+            let utc = chrono::DateTime::<chrono::Utc>::try_from(ts.clone()).unwrap();
+            assert_eq!(utc.to_rfc3339(), "1969-12-31T23:59:58.500+00:00");
+            assert!(Timestamp::from(utc) == ts);
+        }
+    }
+
+    #[test]
+    fn test_timestamp_out_of_range() {
+        for (seconds, nanos) in [(0, -1), (0, 1_000_000_000), (i64::MAX, 0), (i64::MIN, 0)] {
+            let ts = Timestamp { seconds, nanos };
+            assert_eq!(
+                time::OffsetDateTime::try_from(ts.clone()),
+                Err(TimestampOutOfRange(seconds, nanos))
+            );
+            #[cfg(feature = "chrono")]
+            assert_eq!(
+                chrono::DateTime::<chrono::Utc>::try_from(ts),
+                Err(TimestampOutOfRange(seconds, nanos))
+            );
+        }
+    }
+
+    fn d(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_numeric_check() {
+        assert_eq!(Numeric::check(Numeric::max()).unwrap(), Numeric::max());
+        assert_eq!(Numeric::check(Numeric::min()).unwrap(), Numeric::min());
+        assert_eq!(Numeric::check(d("1.5")).unwrap(), d("1.5"));
+        assert!(matches!(
+            Numeric::check(d("100000000000000000000000000000")),
+            Err(NumericError::Overflow(_))
+        ));
+        assert!(matches!(Numeric::check(d("0.0000000001")), Err(NumericError::Scale(_))));
+    }
+
+    #[test]
+    fn test_numeric_arithmetic() {
+        assert_eq!(Numeric::add(&d("1.25"), &d("2.5")).unwrap(), d("3.75"));
+        assert_eq!(Numeric::sub(&d("1"), &d("2.5")).unwrap(), d("-1.5"));
+        assert!(matches!(
+            Numeric::sub(&Numeric::min(), &d("0.000000001")),
+            Err(NumericError::Overflow(_))
+        ));
+        assert_eq!(Numeric::mul(&d("0.00001"), &d("0.00005")).unwrap(), d("0.000000001"));
+        assert!(matches!(Numeric::mul(&Numeric::max(), &d("2")), Err(NumericError::Overflow(_))));
+        assert_eq!(Numeric::div(&d("2"), &d("3")).unwrap(), d("0.666666667"));
+        assert_eq!(Numeric::div(&d("-2"), &d("3")).unwrap(), d("-0.666666667"));
+        assert_eq!(Numeric::div(&d("1"), &d("0")), Err(NumericError::DivisionByZero));
+    }
+}
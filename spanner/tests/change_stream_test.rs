@@ -266,3 +266,122 @@ async fn create_watcher(client: Client, i: usize, now: OffsetDateTime, row: Row)
         }
     })
 }
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn test_change_stream_consumer() {
+    use google_cloud_spanner::change_stream::{ChangeStreamConsumer, InMemoryCheckpointStore};
+    use google_cloud_spanner::mutation::insert;
+    use google_cloud_spanner::value::CommitTimestamp;
+    use std::sync::{Arc, Mutex};
+
+    let client = Client::new(common::DATABASE, ClientConfig::default()).await.unwrap();
+    let start = OffsetDateTime::now_utc();
+    let guild_id = format!("guild_{}", start.unix_timestamp_nanos());
+    client
+        .apply(vec![insert(
+            "Guild",
+            &["GuildId", "OwnerUserId", "UpdatedAt"],
+            &[&guild_id, &"owner", &CommitTimestamp::new()],
+        )])
+        .await
+        .unwrap();
+
+    let consumer = ChangeStreamConsumer::new(
+        client,
+        "GuildChangeStream",
+        start,
+        2,
+        Box::new(InMemoryCheckpointStore::default()),
+    )
+    .with_end_time(start + Duration::from_secs(5))
+    .with_heartbeat_interval(Duration::from_secs(1));
+    let changes = Arc::new(Mutex::new(vec![]));
+    let received = changes.clone();
+    consumer
+        .run(move |record| {
+            let received = received.clone();
+            Box::pin(async move {
+                let mut received = received.lock().unwrap();
+                received.extend(record.data_change_record);
+                Ok::<(), google_cloud_spanner::client::Error>(())
+            })
+        })
+        .await
+        .unwrap();
+
+    {
+        let changes = changes.lock().unwrap();
+        let change = changes
+            .iter()
+            .find(|c| c.mods.iter().any(|m| m.keys.contains(&guild_id)))
+            .unwrap();
+        assert_eq!(change.table_name, "Guild");
+        assert_eq!(change.mod_type, "INSERT");
+    }
+
+    // an error of the handler stops the consumer
+    let result = consumer
+        .run(|record| {
+            Box::pin(async move {
+                if record.data_change_record.is_empty() {
+                    Ok(())
+                } else {
+                    Err(google_cloud_spanner::client::Error::InvalidConfig("stop".to_string()))
+                }
+            })
+        })
+        .await;
+    assert!(matches!(result, Err(google_cloud_spanner::client::Error::InvalidConfig(_))));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_spanner_checkpoint_store() {
+    use google_cloud_spanner::change_stream::{
+        ChangeStreamCheckpointStore, PartitionCheckpoint, SpannerCheckpointStore,
+    };
+    use google_cloud_spanner::key::all_keys;
+    use google_cloud_spanner::mutation::delete;
+    use time::macros::datetime;
+
+    let client = Client::new(common::DATABASE, ClientConfig::default()).await.unwrap();
+    client
+        .apply(vec![delete("ChangeStreamCheckpoint", all_keys())])
+        .await
+        .unwrap();
+    let store = SpannerCheckpointStore::new(client, "ChangeStreamCheckpoint");
+    let checkpoint = |token: &str, timestamp: OffsetDateTime, parents: &[&str]| PartitionCheckpoint {
+        token: token.to_string(),
+        timestamp,
+        parent_partition_tokens: parents.iter().map(|p| p.to_string()).collect(),
+    };
+    store
+        .save(checkpoint("a", datetime!(2024-01-01 0:00 UTC), &[]))
+        .await
+        .unwrap();
+    store
+        .save(checkpoint("b", datetime!(2024-01-01 0:00 UTC), &["a"]))
+        .await
+        .unwrap();
+    store
+        .save(checkpoint("a", datetime!(2024-01-01 0:01 UTC), &[]))
+        .await
+        .unwrap();
+
+    let mut loaded = store.load().await.unwrap();
+    loaded.sort_by(|x, y| x.token.cmp(&y.token));
+    assert_eq!(
+        loaded,
+        vec![
+            checkpoint("a", datetime!(2024-01-01 0:01 UTC), &[]),
+            checkpoint("b", datetime!(2024-01-01 0:00 UTC), &["a"]),
+        ]
+    );
+
+    store.finish("a".to_string()).await.unwrap();
+    assert_eq!(
+        store.load().await.unwrap(),
+        vec![checkpoint("b", datetime!(2024-01-01 0:00 UTC), &["a"])]
+    );
+}
@@ -1,3 +1,7 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use futures_util::{SinkExt, TryStreamExt};
 use serial_test::serial;
 use time::OffsetDateTime;
 
@@ -5,11 +9,15 @@ use common::*;
 use google_cloud_gax::conn::Environment;
 use google_cloud_gax::grpc::{Code, Status};
 use google_cloud_gax::retry::TryAs;
-use google_cloud_spanner::client::{Client, ClientConfig, Error};
+use google_cloud_googleapis::spanner::v1::transaction_options::read_write::ReadLockMode;
+use google_cloud_spanner::client::{Client, ClientConfig, Error, ReadWriteTransactionOption, RowCountEstimate};
+use google_cloud_spanner::export::CsvExportOptions;
 use google_cloud_spanner::key::Key;
-use google_cloud_spanner::retry::TransactionRetry;
+use google_cloud_spanner::retry::{TransactionRetry, TransactionRetrySetting};
 use google_cloud_spanner::row::Row;
+use google_cloud_spanner::schema::Schema;
 use google_cloud_spanner::session::SessionError;
+use google_cloud_spanner::sink::{AsyncMutationBuffer, CloudSpannerSink};
 use google_cloud_spanner::statement::Statement;
 use google_cloud_spanner::value::Timestamp;
 
@@ -138,6 +146,388 @@ async fn test_apply() {
     }
 }
 
+#[tokio::test]
+#[serial]
+async fn test_sink() {
+    let users: Vec<String> = (0..5).map(|x| format!("user_sink_{x}")).collect();
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let now = OffsetDateTime::now_utc();
+    let mut sink = CloudSpannerSink::new(client.clone(), 2);
+    for id in users.iter() {
+        sink.feed(create_user_mutation(id, &now)).await.unwrap();
+    }
+    sink.close().await.unwrap();
+
+    let mut ro = client.read_only_transaction().await.unwrap();
+    for x in users {
+        let record = ro.read("User", &user_columns(), Key::new(&x)).await.unwrap();
+        let rows = all_rows(record).await.unwrap();
+        assert_eq!(rows.len(), 1);
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_async_mutation_buffer() {
+    let users: Vec<String> = (0..5).map(|x| format!("user_buffer_{x}")).collect();
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let now = OffsetDateTime::now_utc();
+    let (sender, receiver) = AsyncMutationBuffer::new(2);
+    let committer = tokio::spawn(receiver.run(client.clone()));
+
+    let tasks: Vec<_> = users
+        .iter()
+        .map(|id| {
+            let sender = sender.clone();
+            let mutation = create_user_mutation(id, &now);
+            tokio::spawn(async move { sender.send(mutation).await })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap().unwrap();
+    }
+    let commit_timestamp = sender.flush().await.unwrap();
+    assert!(commit_timestamp.is_some());
+    drop(sender);
+    assert!(committer.await.unwrap().unwrap().is_none());
+
+    let mut ro = client.read_only_transaction().await.unwrap();
+    for x in users {
+        let record = ro.read("User", &user_columns(), Key::new(&x)).await.unwrap();
+        let rows = all_rows(record).await.unwrap();
+        assert_eq!(rows.len(), 1);
+    }
+}
+
+#[cfg(feature = "uuid")]
+#[tokio::test]
+#[serial]
+async fn test_generate_uuids() {
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let uuid = client.generate_uuid().await.unwrap();
+    assert_eq!(uuid.get_version_num(), 4);
+
+    let uuids = client.generate_uuids(3).await.unwrap();
+    assert_eq!(uuids.len(), 3);
+    assert!(uuids.iter().all(|u| *u != uuid));
+    assert!(client.generate_uuids(0).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_read_write_transaction_retry_setting() {
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let mut retry = TransactionRetrySetting::default();
+    retry.inner.take = 2;
+    let options = ReadWriteTransactionOption::default().with_retry(retry);
+    let result: Result<(Option<Timestamp>, ()), Error> = client
+        .read_write_transaction_with_option(
+            |_tx| {
+                let attempts = attempts.clone();
+                Box::pin(async move {
+                    attempts.fetch_add(1, Ordering::SeqCst);
+                    Err(Error::GRPC(Status::new(Code::Aborted, "aborted")))
+                })
+            },
+            options,
+        )
+        .await;
+    assert!(result.is_err());
+    assert_eq!(attempts.load(Ordering::SeqCst), 3);
+}
+
+/// RetryInfo is google.rpc.RetryInfo, the detail Cloud Spanner adds to an ABORTED status.
+#[derive(Clone, PartialEq, prost::Message)]
+struct RetryInfo {
+    #[prost(message, optional, tag = "1")]
+    retry_delay: Option<prost_types::Duration>,
+}
+
+fn aborted_with_retry_delay(delay: std::time::Duration) -> Status {
+    let retry_info = RetryInfo {
+        retry_delay: Some(delay.try_into().unwrap()),
+    };
+    let details = google_cloud_googleapis::rpc::Status {
+        code: Code::Aborted as i32,
+        message: "aborted".to_string(),
+        details: vec![prost_types::Any {
+            type_url: "type.googleapis.com/google.rpc.RetryInfo".to_string(),
+            value: prost::Message::encode_to_vec(&retry_info),
+        }],
+    };
+    Status::with_details(Code::Aborted, "aborted", prost::Message::encode_to_vec(&details).into())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_read_write_transaction_retry_delay() {
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let attempts = Arc::new(AtomicUsize::new(0));
+    let started = std::time::Instant::now();
+    let result: Result<(Option<Timestamp>, usize), Error> = client
+        .read_write_transaction(|_tx| {
+            let attempts = attempts.clone();
+            Box::pin(async move {
+                match attempts.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(Error::GRPC(aborted_with_retry_delay(std::time::Duration::from_millis(500)))),
+                    attempt => Ok(attempt),
+                }
+            })
+        })
+        .await;
+    assert_eq!(result.unwrap().1, 1);
+    // the default backoff would retry after 10ms
+    assert!(started.elapsed() >= std::time::Duration::from_millis(500));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_schema_from_information_schema() {
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let schema = Schema::from_information_schema(&client).await.unwrap();
+
+    let user = &schema.tables["User"];
+    assert_eq!(user.parent_table, None);
+    assert_eq!(user.primary_key, vec!["UserId"]);
+    assert_eq!(user.columns[0].name, "UserId");
+    assert_eq!(user.columns[0].spanner_type, "STRING(36)");
+    assert_eq!(user.columns[0].ordinal_position, 1);
+    assert!(!user.columns[0].nullable);
+    let nullable = user.columns.iter().find(|c| c.name == "NullableINT64").unwrap();
+    assert!(nullable.nullable);
+    assert_eq!(nullable.spanner_type, "INT64");
+
+    let history = &schema.tables["UserItemHistory"];
+    assert_eq!(history.parent_table.as_deref(), Some("UserItem"));
+    assert_eq!(history.primary_key, vec!["UserId", "ItemId", "UsedAt"]);
+    assert!(!schema.tables.keys().any(|name| name.starts_with("INFORMATION_SCHEMA")));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_is_emulator() {
+    let config = ClientConfig::default();
+    assert!(config.is_emulator());
+    let client = Client::new(DATABASE, config).await.unwrap();
+    assert!(client.is_emulator());
+
+    let config = ClientConfig {
+        environment: Environment::GoogleCloud(Box::new(google_cloud_token::NopeTokenSourceProvider {})),
+        ..Default::default()
+    };
+    assert!(!config.is_emulator());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_ping() {
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let elapsed = client.ping().await.unwrap();
+    assert!(elapsed > std::time::Duration::ZERO);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_export_csv() {
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let sql = "SELECT * FROM (SELECT 1 AS Id, 'a,b' AS Name, CAST(NULL AS STRING) AS Note, [1, 2] AS List
+        UNION ALL SELECT 2, 'say \"hi\"', '', NULL) ORDER BY Id";
+    let mut csv = vec![];
+    let count = client
+        .export_csv(Statement::new(sql), &mut csv, CsvExportOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "Id,Name,Note,List\n1,\"a,b\",,\"[\"\"1\"\", \"\"2\"\"]\"\n2,\"say \"\"hi\"\"\",\"\",\n"
+    );
+
+    let options = CsvExportOptions {
+        delimiter: '\t',
+        null_string: "NULL".to_string(),
+        ..Default::default()
+    };
+    let mut csv = vec![];
+    let count = client
+        .export_csv(
+            Statement::new("SELECT 1 AS Id, NULL AS Note FROM UNNEST([1]) WHERE FALSE"),
+            &mut csv,
+            options,
+        )
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+    assert_eq!(String::from_utf8(csv).unwrap(), "Id\tNote\n");
+}
+
+#[tokio::test]
+#[serial]
+async fn test_estimate_row_count() {
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let now = OffsetDateTime::now_utc();
+    let user_id = format!("user_estimate_{}", now.unix_timestamp());
+    client.apply(vec![create_user_mutation(&user_id, &now)]).await.unwrap();
+
+    let mut tx = client.single().await.unwrap();
+    let mut iter = tx.query(Statement::new("SELECT COUNT(*) FROM User")).await.unwrap();
+    let count = iter.next().await.unwrap().unwrap().column::<i64>(0).unwrap() as u64;
+    drop(iter);
+    drop(tx);
+
+    let estimate = client.estimate_row_count("User", 100.0).await.unwrap();
+    assert_eq!(
+        estimate,
+        RowCountEstimate {
+            sample_count: count,
+            estimated_total: count,
+            confidence_95_percent_interval: (count, count),
+        }
+    );
+
+    let estimate = client.estimate_row_count("User", 50.0).await.unwrap();
+    let (lower, upper) = estimate.confidence_95_percent_interval;
+    assert!(estimate.sample_count <= count);
+    assert!(lower >= estimate.sample_count && lower <= estimate.estimated_total && estimate.estimated_total <= upper);
+
+    assert!(matches!(
+        client.estimate_row_count("User", 0.0).await,
+        Err(Error::InvalidConfig(_))
+    ));
+    assert!(matches!(
+        client.estimate_row_count("User`; --", 10.0).await,
+        Err(Error::InvalidConfig(_))
+    ));
+}
+
+#[cfg(all(feature = "serde", feature = "json"))]
+#[tokio::test]
+#[serial]
+async fn test_spanner_log() {
+    use futures_util::TryStreamExt;
+    use google_cloud_spanner::log::SpannerLog;
+
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let log = SpannerLog::<(String, i64)>::new(client, "EventLog").with_page_size(2);
+    let latest = log.latest_sequence().await.unwrap();
+
+    let appends = (0..5).map(|i| {
+        let log = &log;
+        async move { log.append(&(format!("event{i}"), i)).await.unwrap() }
+    });
+    let mut sequence_numbers = futures_util::future::join_all(appends).await;
+    sequence_numbers.sort();
+    assert_eq!(sequence_numbers, (latest + 1..=latest + 5).collect::<Vec<_>>());
+    assert_eq!(log.latest_sequence().await.unwrap(), latest + 5);
+
+    let entries: Vec<_> = log.read_from(latest + 1).try_collect().await.unwrap();
+    assert_eq!(entries.iter().map(|e| e.sequence_number).collect::<Vec<_>>(), sequence_numbers);
+    let mut values: Vec<i64> = entries.iter().map(|e| e.entry.1).collect();
+    values.sort();
+    assert_eq!(values, vec![0, 1, 2, 3, 4]);
+    assert!(entries.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+
+    let entries: Vec<_> = log.read_from(latest + 5).try_collect().await.unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].entry, (format!("event{}", entries[0].entry.1), entries[0].entry.1));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_distributed_lock() {
+    use google_cloud_spanner::lock::DistributedLock;
+    use std::time::Duration;
+
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let lock = DistributedLock::new(client, "DistributedLock");
+    let name = format!("lock_{}", OffsetDateTime::now_utc().unix_timestamp_nanos());
+    let ttl = Duration::from_secs(1);
+    assert!(matches!(
+        lock.try_acquire(&name, Duration::ZERO, "owner1").await,
+        Err(Error::InvalidConfig(_))
+    ));
+
+    let guard = lock.try_acquire(&name, ttl, "owner1").await.unwrap().unwrap();
+    assert_eq!(guard.lock_name(), name);
+    assert!(lock.try_acquire(&name, ttl, "owner2").await.unwrap().is_none());
+
+    // the background renewal keeps the lock beyond its ttl
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(!guard.is_lost());
+    assert!(lock.try_acquire(&name, ttl, "owner2").await.unwrap().is_none());
+    lock.renew(&guard).await.unwrap();
+
+    guard.release().await.unwrap();
+    let guard = lock.try_acquire(&name, ttl, "owner2").await.unwrap().unwrap();
+
+    // without renewal the lock expires and can be taken over
+    drop(guard);
+    tokio::time::sleep(Duration::from_millis(1200)).await;
+    let guard = lock.try_acquire(&name, ttl, "owner3").await.unwrap().unwrap();
+    let stale = lock
+        .try_acquire(&name, Duration::from_secs(60), "owner3")
+        .await
+        .unwrap()
+        .unwrap();
+    guard.release().await.unwrap();
+    assert!(lock.renew(&stale).await.is_err());
+    assert!(stale.is_lost());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_list_and_delete_sessions() {
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let sessions = client.list_sessions(None).try_collect::<Vec<_>>().await.unwrap();
+    assert!(!sessions.is_empty());
+
+    let name = &sessions[0].name;
+    client.delete_session(name).await.unwrap();
+    let sessions = client.list_sessions(None).try_collect::<Vec<_>>().await.unwrap();
+    assert!(sessions.iter().all(|s| &s.name != name));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_read_write_transaction_optimistic() {
+    let now = OffsetDateTime::now_utc();
+    let user_id = format!("user_optimistic_{}", now.unix_timestamp());
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    client.apply(vec![create_user_mutation(&user_id, &now)]).await.unwrap();
+
+    let options = ReadWriteTransactionOption::default().with_read_lock_mode(ReadLockMode::Optimistic);
+    let result: Result<(Option<Timestamp>, i64), Error> = client
+        .read_write_transaction_with_option(
+            |tx| {
+                let user_id = user_id.to_string();
+                Box::pin(async move {
+                    let row = tx
+                        .read_row("User", &["NotNullINT64"], Key::new(&user_id))
+                        .await?
+                        .unwrap();
+                    let value = row.column_by_name::<i64>("NotNullINT64")? + 1;
+                    let mut stmt = Statement::new("UPDATE User SET NotNullINT64 = @Value WHERE UserId = @UserId");
+                    stmt.add_param("Value", &value);
+                    stmt.add_param("UserId", &user_id);
+                    tx.update(stmt).await?;
+                    Ok(value)
+                })
+            },
+            options,
+        )
+        .await;
+    let (_, value) = result.unwrap();
+
+    let mut single = client.single().await.unwrap();
+    let row = single
+        .read_row("User", &["NotNullINT64"], Key::new(&user_id))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(value, row.column_by_name::<i64>("NotNullINT64").unwrap());
+}
+
 #[tokio::test]
 #[serial]
 async fn test_apply_at_least_once() {
@@ -234,6 +624,19 @@ async fn test_begin_read_write_transaction_retry() {
     assert_eq!(retry_count, 5);
 }
 
+#[test]
+fn test_error_into_status() {
+    let status: Status = Error::GRPC(Status::new(Code::Aborted, "aborted")).into();
+    assert_eq!(status.code(), Code::Aborted);
+    assert_eq!(status.message(), "aborted");
+
+    let status: Status = Error::InvalidSession(SessionError::SessionGetTimeout).into();
+    assert_eq!(status.code(), Code::DeadlineExceeded);
+
+    let status: Status = Error::InvalidConfig("invalid".to_string()).into();
+    assert_eq!(status.code(), Code::InvalidArgument);
+}
+
 #[tokio::test]
 async fn test_with_auth() {
     let config = ClientConfig::default().with_auth().await.unwrap();
@@ -241,3 +644,101 @@ async fn test_with_auth() {
         unreachable!()
     }
 }
+
+#[tokio::test]
+#[serial]
+async fn test_transaction_recorder() {
+    use google_cloud_spanner::recorder::{RecorderConfig, TransactionRecorder};
+
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    let recorder = TransactionRecorder::new(client.clone(), RecorderConfig::default());
+    let user_id = format!("user_recorder_{}", OffsetDateTime::now_utc().unix_timestamp_nanos());
+
+    let (commit_timestamp, _) = recorder
+        .read_write_transaction("trace-1", |tx| {
+            let mutation = create_user_mutation(&user_id, &OffsetDateTime::now_utc());
+            Box::pin(async move {
+                tx.buffer_write(vec![mutation]);
+                Ok::<(), Error>(())
+            })
+        })
+        .await
+        .unwrap();
+    assert!(commit_timestamp.is_some());
+
+    let result = recorder
+        .read_write_transaction("trace-2", |tx| {
+            let mutation = create_user_item_mutation(&user_id, 1);
+            Box::pin(async move {
+                tx.buffer_write(vec![mutation]);
+                Err::<(), Error>(Error::GRPC(Status::new(Code::FailedPrecondition, "rolled back")))
+            })
+        })
+        .await;
+    assert!(result.is_err());
+
+    let mut tx = client.single().await.unwrap();
+    let row = tx.read_row("User", &["UserId"], Key::new(&user_id)).await.unwrap();
+    assert!(row.is_some());
+    let row = tx
+        .read_row("UserItem", &["UserId"], Key::composite(&[&user_id, &1]))
+        .await
+        .unwrap();
+    assert!(row.is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_transaction_tag() {
+    use google_cloud_spanner::transaction::CallOptions;
+
+    let now = OffsetDateTime::now_utc();
+    let user_id = format!("user_tx_tag_{}", now.unix_timestamp_nanos());
+    let client = Client::new(DATABASE, ClientConfig::default()).await.unwrap();
+    client.apply(vec![create_user_mutation(&user_id, &now)]).await.unwrap();
+
+    let options = ReadWriteTransactionOption {
+        begin_options: CallOptions {
+            transaction_tag: Some("test_transaction_tag".to_string()),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let result: Result<(Option<Timestamp>, i64), Error> = client
+        .read_write_transaction_with_option(
+            |tx| {
+                let mut stmt = Statement::new("UPDATE User SET NullableString = 'tagged' WHERE UserId = @UserId");
+                stmt.add_param("UserId", &user_id);
+                let user_id = user_id.to_string();
+                Box::pin(async move {
+                    let row = tx.read_row("User", &["UserId"], Key::new(&user_id)).await?;
+                    assert!(row.is_some());
+                    Ok(tx.update(stmt).await?)
+                })
+            },
+            options,
+        )
+        .await;
+    let (commit_timestamp, updated) = result.unwrap();
+    assert!(commit_timestamp.is_some());
+    assert_eq!(updated, 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_rate_limit() {
+    use std::time::{Duration, Instant};
+
+    let config = ClientConfig::default().with_rate_limit(20.0);
+    let client = Client::new(DATABASE, config).await.unwrap();
+    let started = Instant::now();
+    for _ in 0..5 {
+        let mut tx = client.single().await.unwrap();
+        let iter = tx.query(Statement::new("SELECT 1")).await.unwrap();
+        assert_eq!(all_rows(iter).await.unwrap().len(), 1);
+    }
+    assert!(started.elapsed() >= Duration::from_millis(200));
+
+    let config = ClientConfig::default().with_rate_limit(1e-20);
+    assert!(matches!(Client::new(DATABASE, config).await, Err(Error::InvalidConfig(_))));
+}
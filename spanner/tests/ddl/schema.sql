@@ -49,4 +49,27 @@ CREATE TABLE Guild
     GuildId STRING(36) NOT NULL,
     OwnerUserId STRING(36) NOT NULL,
     UpdatedAt TIMESTAMP NOT NULL OPTIONS (allow_commit_timestamp=true)
-) PRIMARY KEY(GuildId);
\ No newline at end of file
+) PRIMARY KEY(GuildId);
+
+CREATE TABLE EventLog
+(
+    sequence_number INT64 NOT NULL,
+    entry BYTES(MAX) NOT NULL,
+    timestamp TIMESTAMP NOT NULL OPTIONS (allow_commit_timestamp=true)
+) PRIMARY KEY(sequence_number);
+
+CREATE TABLE DistributedLock
+(
+    lock_name STRING(MAX) NOT NULL,
+    owner_id STRING(MAX) NOT NULL,
+    expires_at TIMESTAMP NOT NULL
+) PRIMARY KEY(lock_name);
+
+CREATE CHANGE STREAM GuildChangeStream FOR Guild;
+
+CREATE TABLE ChangeStreamCheckpoint
+(
+    partition_token STRING(MAX) NOT NULL,
+    timestamp TIMESTAMP NOT NULL,
+    parent_partition_tokens ARRAY<STRING(MAX)> NOT NULL
+) PRIMARY KEY(partition_token);
@@ -2,14 +2,20 @@ use bigdecimal::BigDecimal;
 use std::collections::HashMap;
 use std::ops::Add;
 
+use futures_util::{StreamExt, TryStreamExt};
 use serial_test::serial;
 use time::{Duration, OffsetDateTime};
 
 use common::*;
-use google_cloud_spanner::key::Key;
+use google_cloud_googleapis::spanner::v1::execute_sql_request::QueryMode;
+use google_cloud_googleapis::spanner::v1::PartitionOptions;
+use google_cloud_spanner::key::{Key, KeyRange, KeySet, RangeKind};
+use google_cloud_spanner::pagination::PaginatedReader;
 use google_cloud_spanner::row::Row;
-use google_cloud_spanner::statement::Statement;
+use google_cloud_spanner::statement::{Kinds, Statement, StructValue, ToKind, ToStruct, Types};
+use google_cloud_spanner::transaction::{QueryOptions, ReadOptions};
 use google_cloud_spanner::transaction_ro::ReadOnlyTransaction;
+use google_cloud_spanner::value::TimestampBound;
 
 mod common;
 
@@ -225,6 +231,27 @@ async fn test_batch_partition_query_and_read() {
         let user_id = format!("user_partitionx_{x}");
         assert_user_row(map.get(&user_id).unwrap(), &user_id, &now, &ts)
     });
+
+    // partition a key range
+    let range = KeyRange::new(
+        Key::new(&"user_partitionx_"),
+        Key::new(&"user_partitionx_~"),
+        RangeKind::ClosedOpen,
+    );
+    let po = PartitionOptions {
+        partition_size_bytes: 0,
+        max_partitions: 4,
+    };
+    let partitions = tx
+        .partition_read_with_option("User", &["UserId"], range, Some(po), ReadOptions::default(), false)
+        .await
+        .unwrap();
+    let mut count = 0;
+    for p in partitions.into_iter() {
+        let reader = tx.execute(p, None).await.unwrap();
+        count += all_rows(reader).await.unwrap().len();
+    }
+    assert_eq!(20000, count);
 }
 
 async fn test_query(count: usize, prefix: &str) {
@@ -307,6 +334,113 @@ async fn test_many_records_struct() {
     assert_eq!(4500, characters.len());
 }
 
+struct Point {
+    x: i64,
+    y: String,
+}
+
+impl ToStruct for Point {
+    fn to_kinds(&self) -> Kinds {
+        vec![("x", self.x.to_kind()), ("y", self.y.to_kind())]
+    }
+
+    fn get_types() -> Types {
+        vec![("x", i64::get_type()), ("y", String::get_type())]
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_struct_params() {
+    let data_client = create_data_client().await;
+    let mut tx = data_client.read_only_transaction().await.unwrap();
+
+    let mut stmt = Statement::new("SELECT x, y FROM UNNEST(@points) ORDER BY x");
+    stmt.add_param(
+        "points",
+        &vec![
+            Point {
+                x: 2,
+                y: "b".to_string(),
+            },
+            Point {
+                x: 1,
+                y: "a".to_string(),
+            },
+        ],
+    );
+    let rows = execute_query(&mut tx, stmt).await;
+    assert_eq!(2, rows.len());
+    assert_eq!(1, rows[0].column_by_name::<i64>("x").unwrap());
+    assert_eq!("a", rows[0].column_by_name::<String>("y").unwrap());
+    assert_eq!(2, rows[1].column_by_name::<i64>("x").unwrap());
+    assert_eq!("b", rows[1].column_by_name::<String>("y").unwrap());
+
+    let mut stmt = Statement::new("SELECT @point.x AS x, @point.y AS y");
+    stmt.add_param(
+        "point",
+        &Point {
+            x: 3,
+            y: "c".to_string(),
+        },
+    );
+    let rows = execute_query(&mut tx, stmt).await;
+    assert_eq!(3, rows[0].column_by_name::<i64>("x").unwrap());
+    assert_eq!("c", rows[0].column_by_name::<String>("y").unwrap());
+
+    let mut stmt = Statement::new("SELECT p.x, p.y FROM UNNEST([@a, @b]) AS p ORDER BY p.x");
+    stmt.add_struct_param("a", &StructValue::new().field("x", &5_i64).field("y", &"e"));
+    stmt.add_struct_param("b", &StructValue::new().field("x", &4_i64).field("y", &"d"));
+    let rows = execute_query(&mut tx, stmt).await;
+    assert_eq!(2, rows.len());
+    assert_eq!(4, rows[0].column_by_name::<i64>("x").unwrap());
+    assert_eq!("d", rows[0].column_by_name::<String>("y").unwrap());
+    assert_eq!(5, rows[1].column_by_name::<i64>("x").unwrap());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_query_stats() {
+    let data_client = create_data_client().await;
+    let mut tx = data_client.single().await.unwrap();
+    let options = QueryOptions {
+        mode: QueryMode::Profile,
+        ..Default::default()
+    };
+    let mut iter = tx
+        .query_with_option(Statement::new("SELECT 1 AS x UNION ALL SELECT 2 AS x"), options)
+        .await
+        .unwrap();
+    assert!(iter.stats().is_none());
+    let mut count = 0;
+    while iter.next().await.unwrap().is_some() {
+        count += 1;
+    }
+    assert_eq!(count, 2);
+    let query_stats = iter.stats().unwrap().query_stats.as_ref().unwrap();
+    assert!(query_stats.fields.contains_key("elapsed_time"));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_query_plan() {
+    let data_client = create_data_client().await;
+    let mut tx = data_client.single().await.unwrap();
+    let options = QueryOptions {
+        mode: QueryMode::Plan,
+        ..Default::default()
+    };
+    let mut iter = tx
+        .query_with_option(Statement::new("SELECT UserId FROM User"), options)
+        .await
+        .unwrap();
+    // the query is not executed, and the emulator does not return the plan
+    assert!(iter.next().await.unwrap().is_none());
+    if !data_client.is_emulator() {
+        assert!(!iter.query_plan().unwrap().plan_nodes.is_empty());
+    }
+}
+
 #[tokio::test]
 #[serial]
 async fn test_read_row() {
@@ -344,6 +478,172 @@ async fn test_read_multi_row() {
         .await
         .unwrap();
     assert_eq!(2, all_rows(row).await.unwrap().len());
+
+    let key_set =
+        KeySet::from(Key::new(&user_id)).add_range(KeyRange::closed_closed(Key::new(&user_id2), Key::new(&user_id2)));
+    let row = tx.read("User", &["UserId"], key_set).await.unwrap();
+    assert_eq!(2, all_rows(row).await.unwrap().len());
+
+    let row = tx.read("User", &["UserId"], KeyRange::all()).await.unwrap();
+    assert!(all_rows(row).await.unwrap().len() >= 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_array_params() {
+    let now = OffsetDateTime::now_utc();
+    let user_id = format!("user_array_{}", now.unix_timestamp());
+    let user_id2 = format!("user_array_{}", now.unix_timestamp() + 1);
+    let data_client = create_data_client().await;
+    let _ = data_client
+        .apply(vec![
+            create_user_mutation(&user_id, &now),
+            create_user_mutation(&user_id2, &now),
+        ])
+        .await
+        .unwrap();
+
+    let mut tx = data_client.read_only_transaction().await.unwrap();
+    let mut stmt = Statement::new("SELECT UserId FROM User WHERE UserId IN UNNEST(@ids) ORDER BY UserId");
+    stmt.add_param(
+        "ids",
+        &vec![user_id.clone(), user_id2.clone(), "user_array_missing".to_string()],
+    );
+    let rows = all_rows(tx.query(stmt).await.unwrap()).await.unwrap();
+    let ids: Vec<String> = rows.iter().map(|row| row.column(0).unwrap()).collect();
+    assert_eq!(ids, vec![user_id, user_id2]);
+
+    let values = vec![Some(1), None, Some(3)];
+    let mut stmt = Statement::new("SELECT @values, (SELECT COUNT(*) FROM UNNEST(@values) AS v WHERE v IS NULL)");
+    stmt.add_param("values", &values);
+    let rows = all_rows(tx.query(stmt).await.unwrap()).await.unwrap();
+    assert_eq!(rows[0].column::<Vec<Option<i64>>>(0).unwrap(), values);
+    assert_eq!(rows[0].column::<i64>(1).unwrap(), 1);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_row_stream() {
+    let data_client = create_data_client().await;
+    let mut tx = data_client.read_only_transaction().await.unwrap();
+    let stmt = Statement::new("SELECT x FROM UNNEST(GENERATE_ARRAY(1, 1000)) AS x ORDER BY x");
+    let rows: Vec<Row> = tx
+        .query(stmt.clone())
+        .await
+        .unwrap()
+        .into_stream()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(rows.len(), 1000);
+
+    // dropping the stream early leaves the transaction usable
+    let stream = tx.query(stmt.clone()).await.unwrap().into_stream();
+    let first: Vec<Row> = stream.take(3).try_collect().await.unwrap();
+    assert_eq!(first[2].column::<i64>(0).unwrap(), 3);
+    let rows = execute_query(&mut tx, stmt).await;
+    assert_eq!(rows.len(), 1000);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_read_timestamp() {
+    let data_client = create_data_client().await;
+    let stmt = Statement::new("SELECT 1");
+
+    let mut tx = data_client.single().await.unwrap();
+    let mut iter = tx.query(stmt.clone()).await.unwrap();
+    assert!(iter.read_timestamp().is_none());
+    assert!(iter.next().await.unwrap().is_some());
+    let single_rts = iter.read_timestamp().unwrap();
+    drop(iter);
+    assert!(tx.read_timestamp().is_none());
+    drop(tx);
+
+    let mut tx = data_client.read_only_transaction().await.unwrap();
+    let rts = tx.read_timestamp().unwrap();
+    assert!(rts >= single_rts);
+    let mut iter = tx.query(stmt.clone()).await.unwrap();
+    assert!(iter.next().await.unwrap().is_some());
+    assert!(iter.read_timestamp().is_none());
+    drop(iter);
+    drop(tx);
+
+    let mut tx = data_client
+        .single_with_timestamp_bound(TimestampBound::read_timestamp(rts.into()))
+        .await
+        .unwrap();
+    let mut iter = tx.query(stmt).await.unwrap();
+    assert!(iter.next().await.unwrap().is_some());
+    assert_eq!(iter.read_timestamp(), Some(rts));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_query_prefetched() {
+    let data_client = create_data_client().await;
+    let stmt = Statement::new("SELECT x FROM UNNEST(GENERATE_ARRAY(1, 1000)) AS x ORDER BY x");
+    let tx = data_client.read_only_transaction().await.unwrap();
+    let (rows, task) = tx.query_prefetched(stmt.clone(), 10);
+    let values: Vec<i64> = rows.map(|row| row.unwrap().column::<i64>(0).unwrap()).collect().await;
+    assert_eq!(values, (1..=1000).collect::<Vec<_>>());
+    task.await.unwrap().unwrap();
+
+    // dropping the stream stops the reading task
+    let tx = data_client.single().await.unwrap();
+    let (rows, task) = tx.query_prefetched(stmt, 10);
+    let first: Vec<Row> = Box::pin(rows).take(3).try_collect().await.unwrap();
+    assert_eq!(first[2].column::<i64>(0).unwrap(), 3);
+    task.await.unwrap().unwrap();
+
+    let tx = data_client.single().await.unwrap();
+    let (rows, task) = tx.query_prefetched(Statement::new("SELECT * FROM NotFoundTable"), 10);
+    let rows: Vec<Result<Row, _>> = rows.collect().await;
+    assert_eq!(rows.len(), 1);
+    assert!(rows[0].is_err());
+    assert!(task.await.unwrap().is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_paginated_reader() {
+    let now = OffsetDateTime::now_utc();
+    let prefix = format!("user_page_{}_", now.unix_timestamp());
+    let user_ids: Vec<String> = (0..5).map(|i| format!("{prefix}{i}")).collect();
+    let data_client = create_data_client().await;
+    data_client
+        .apply(user_ids.iter().map(|id| create_user_mutation(id, &now)).collect())
+        .await
+        .unwrap();
+
+    let mut tx = data_client.read_only_transaction().await.unwrap();
+    let range = KeyRange::closed_closed(Key::new(&user_ids[0]), Key::new(&user_ids[4]));
+    let mut reader = PaginatedReader::new("User", &["UserId"], 1, range, 2);
+    let mut pages = vec![];
+    while let Some(rows) = reader.next_page(&mut tx).await.unwrap() {
+        let ids: Vec<String> = rows.iter().map(|r| r.column_by_name("UserId").unwrap()).collect();
+        pages.push(ids);
+    }
+    assert_eq!(
+        pages,
+        vec![
+            user_ids[0..2].to_vec(),
+            user_ids[2..4].to_vec(),
+            user_ids[4..5].to_vec()
+        ]
+    );
+
+    let range = KeyRange::closed_open(Key::new(&user_ids[0]), Key::new(&user_ids[4]));
+    let mut reader = PaginatedReader::new("User", &["UserId"], 1, range, 2);
+    assert_eq!(reader.next_page(&mut tx).await.unwrap().unwrap().len(), 2);
+    assert_eq!(reader.next_page(&mut tx).await.unwrap().unwrap().len(), 2);
+    assert!(reader.next_page(&mut tx).await.unwrap().is_none());
+
+    let mut reader = PaginatedReader::new("User", &["UserId"], 2, KeyRange::all(), 2);
+    match reader.next_page(&mut tx).await {
+        Err(status) => assert_eq!(status.code(), google_cloud_gax::grpc::Code::InvalidArgument),
+        Ok(_) => panic!("key_columns larger than the columns must be rejected"),
+    }
 }
 
 #[tokio::test]
@@ -383,3 +683,103 @@ async fn test_big_decimal() {
         row.column::<BigDecimal>(6).unwrap().to_string()
     );
 }
+
+#[tokio::test]
+#[serial]
+async fn test_big_decimal_array() {
+    let client = create_data_client().await;
+    let mut tx = client.read_only_transaction().await.unwrap();
+    let values: Vec<Option<BigDecimal>> = [
+        Some("-99999999999999999999999999999.999999999"),
+        Some("-0.000000001"),
+        None,
+        Some("0.000000001"),
+        Some("99999999999999999999999999999.999999999"),
+    ]
+    .iter()
+    .map(|v| v.map(|v| v.parse().unwrap()))
+    .collect();
+    let mut stmt = Statement::new(
+        "SELECT @values, ARRAY(SELECT v FROM UNNEST(@values) AS v WITH OFFSET o WHERE v IS NOT NULL ORDER BY o)",
+    );
+    stmt.add_param("values", &values);
+    let mut iter = tx.query(stmt).await.unwrap();
+    let row = iter.next().await.unwrap().unwrap();
+    assert_eq!(row.column::<Vec<Option<BigDecimal>>>(0).unwrap(), values);
+    let not_null: Vec<BigDecimal> = values.into_iter().flatten().collect();
+    assert_eq!(row.column::<Vec<BigDecimal>>(1).unwrap(), not_null);
+    assert!(row.column::<Vec<BigDecimal>>(0).is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_read_with_limit() {
+    let now = OffsetDateTime::now_utc();
+    let user_ids: Vec<String> = (0..3)
+        .map(|i| format!("user_limit_{}_{}", now.unix_timestamp(), i))
+        .collect();
+    let data_client = create_data_client().await;
+    let mutations = user_ids.iter().map(|id| create_user_mutation(id, &now)).collect();
+    data_client.apply(mutations).await.unwrap();
+
+    let keys: Vec<Key> = user_ids.iter().map(|id| Key::new(id)).collect();
+    let mut tx = data_client.read_only_transaction().await.unwrap();
+    for (limit, expected) in [(2, 2), (0, 3)] {
+        let options = ReadOptions {
+            limit,
+            ..Default::default()
+        };
+        let iter = tx
+            .read_with_option("User", &["UserId"], keys.clone(), options)
+            .await
+            .unwrap();
+        assert_eq!(all_rows(iter).await.unwrap().len(), expected);
+    }
+
+    let options = ReadOptions {
+        limit: -1,
+        ..Default::default()
+    };
+    let result = tx.read_with_option("User", &["UserId"], keys, options).await;
+    assert_eq!(result.err().unwrap().code(), google_cloud_gax::grpc::Code::InvalidArgument);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_request_options() {
+    use google_cloud_googleapis::spanner::v1::request_options::Priority;
+    use google_cloud_spanner::transaction::CallOptions;
+
+    let now = OffsetDateTime::now_utc();
+    let user_id = format!("user_request_options_{}", now.unix_timestamp());
+    let data_client = create_data_client().await;
+    data_client
+        .apply(vec![create_user_mutation(&user_id, &now)])
+        .await
+        .unwrap();
+
+    let call_options = CallOptions {
+        priority: Some(Priority::High),
+        request_tag: Some("test_request_options".to_string()),
+        ..Default::default()
+    };
+    let mut tx = data_client.read_only_transaction().await.unwrap();
+    let mut stmt = Statement::new("SELECT UserId FROM User WHERE UserId = @UserId");
+    stmt.add_param("UserId", &user_id);
+    let options = QueryOptions {
+        call_options: call_options.clone(),
+        ..Default::default()
+    };
+    let iter = tx.query_with_option(stmt, options).await.unwrap();
+    assert_eq!(all_rows(iter).await.unwrap().len(), 1);
+
+    let options = ReadOptions {
+        call_options,
+        ..Default::default()
+    };
+    let iter = tx
+        .read_with_option("User", &["UserId"], Key::new(&user_id), options)
+        .await
+        .unwrap();
+    assert_eq!(all_rows(iter).await.unwrap().len(), 1);
+}
@@ -5,6 +5,7 @@ use common::*;
 use google_cloud_spanner::key::Key;
 use google_cloud_spanner::row::Row;
 use google_cloud_spanner::statement::Statement;
+use google_cloud_spanner::transaction_rw::{with_transaction_context, TransactionContext};
 
 mod common;
 
@@ -106,6 +107,99 @@ async fn test_rollback() {
     assert_user_row(&row, &past_user, &now, &ts);
 }
 
+#[tokio::test]
+#[serial]
+async fn test_update_returning() {
+    //set up test data
+    let now = OffsetDateTime::now_utc();
+    let data_client = create_data_client().await;
+    let user_id = format!("user_returning_{}", now.unix_timestamp());
+    data_client
+        .apply(vec![
+            create_user_mutation(&user_id, &now),
+            create_user_character_mutation(&user_id, 1),
+            create_user_character_mutation(&user_id, 2),
+        ])
+        .await
+        .unwrap();
+
+    //test
+    let mut tx = data_client.begin_read_write_transaction().await.unwrap();
+    let result = async {
+        let mut stmt = Statement::new(
+            "UPDATE UserCharacter SET Level = Level + 1 WHERE UserId = @UserId THEN RETURN CharacterId, Level",
+        );
+        stmt.add_param("UserId", &user_id);
+        let mut iter = tx.update_returning(stmt).await?;
+        let mut rows = vec![];
+        while let Some(row) = iter.next().await? {
+            rows.push((
+                row.column_by_name::<i64>("CharacterId").unwrap(),
+                row.column_by_name::<i64>("Level").unwrap(),
+            ));
+        }
+        Ok::<_, google_cloud_gax::grpc::Status>(rows)
+    }
+    .await;
+    let (_, mut rows) = tx.end(result, None).await.unwrap();
+    rows.sort();
+    assert_eq!(rows, vec![(1, 2), (2, 2)]);
+}
+
+#[tokio::test]
+#[serial]
+async fn test_batch_update_partial() {
+    let now = OffsetDateTime::now_utc();
+    let data_client = create_data_client().await;
+    let user_id = format!("user_batch_{}", now.unix_timestamp());
+    data_client
+        .apply(vec![create_user_mutation(&user_id, &now)])
+        .await
+        .unwrap();
+
+    let statements = || {
+        let sqls = [
+            "UPDATE User SET NullableString = 'batch' WHERE UserId = @UserId",
+            "UPDATE User SET NotNullINT64 = CAST(NullableString AS INT64) WHERE UserId = @UserId",
+            "UPDATE User SET NullableString = 'not executed' WHERE UserId = @UserId",
+        ];
+        sqls.iter()
+            .map(|sql| {
+                let mut stmt = Statement::new(*sql);
+                stmt.add_param("UserId", &user_id);
+                stmt
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let mut tx = data_client.begin_read_write_transaction().await.unwrap();
+    let result = tx.batch_update_partial(statements()[0..1].to_vec()).await;
+    let (row_counts, status) = tx.end(result, None).await.unwrap().1;
+    assert_eq!(row_counts, vec![1]);
+    assert!(status.is_none());
+    drop(tx);
+
+    // The emulator fails the whole RPC instead of returning the row counts before the failed statement.
+    let mut tx = data_client.begin_read_write_transaction().await.unwrap();
+    let result = tx.batch_update(statements()).await;
+    assert!(result.is_err());
+    let _ = tx.end(result, None).await;
+}
+
+#[tokio::test]
+#[serial]
+async fn test_session_name_and_transaction_id() {
+    let data_client = create_data_client().await;
+    let tx = data_client.begin_read_write_transaction().await.unwrap();
+    assert!(tx.session_name().starts_with(&format!("{DATABASE}/sessions/")));
+    assert!(!tx.transaction_id().unwrap().is_empty());
+    drop(tx);
+
+    let tx = data_client.single().await.unwrap();
+    assert!(tx.session_name().starts_with(&format!("{DATABASE}/sessions/")));
+    assert!(tx.transaction_id().is_none());
+}
+
 async fn assert_data(
     user_id: &str,
     now: &OffsetDateTime,
@@ -161,3 +255,43 @@ async fn assert_data(
     );
     assert!(user_characters.is_empty());
 }
+
+#[tokio::test]
+#[serial]
+async fn test_transaction_context() {
+    assert!(TransactionContext::current().is_none());
+    let data_client = create_data_client().await;
+    let tx = data_client.begin_read_write_transaction().await.unwrap();
+    let context = with_transaction_context(&tx, async {
+        tokio::task::yield_now().await;
+        TransactionContext::current()
+    })
+    .await
+    .unwrap();
+    assert_eq!(context.session_name, tx.session_name());
+    assert_eq!(context.transaction_id, tx.transaction_id().unwrap());
+    assert!(TransactionContext::current().is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn test_buffered_mutations() {
+    let now = OffsetDateTime::now_utc();
+    let data_client = create_data_client().await;
+    let user_id = format!("user_buffered_{}", now.unix_timestamp());
+
+    let mut tx = data_client.begin_read_write_transaction().await.unwrap();
+    assert_eq!(tx.buffered_mutation_count(), 0);
+    assert_eq!(tx.estimated_commit_size_bytes(), 0);
+
+    tx.buffer_write(vec![create_user_mutation(&user_id, &now)]);
+    let user_size = tx.estimated_commit_size_bytes();
+    assert!(user_size > 0);
+    tx.buffer_write(vec![create_user_item_mutation(&user_id, 1)]);
+    assert_eq!(tx.buffered_mutation_count(), 2);
+    assert_eq!(tx.buffered_mutations()[1], create_user_item_mutation(&user_id, 1));
+    assert!(tx.estimated_commit_size_bytes() > user_size);
+
+    let result: Result<(), google_cloud_spanner::client::Error> = Ok(());
+    tx.end(result, None).await.unwrap();
+}